@@ -0,0 +1,264 @@
+use memchr::memmem;
+
+use crate::error::ReaderError;
+use crate::event::EventReader;
+use crate::record::{Record, RecordReader};
+
+/// Common adapters checked by [`AdapterDetector`], as `(name, sequence)`.
+pub const KNOWN_ADAPTERS: &[(&str, &[u8])] = &[
+    ("Illumina Universal", b"AGATCGGAAGAGC"),
+    ("Nextera", b"CTGTCTCTTATACACATCT"),
+    ("Illumina Small RNA", b"TGGAATTCTCGG"),
+];
+
+/// Bases from the start of a known adapter used for detection.
+const DETECTION_PREFIX: usize = 12;
+
+/// Named 3' adapter sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adapter {
+    pub name: String,
+    pub seq: Vec<u8>,
+}
+
+impl Adapter {
+    /// Creates an adapter.
+    pub fn new(name: impl Into<String>, seq: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.into(),
+            seq: seq.into(),
+        }
+    }
+}
+
+/// Removes 3' adapters and everything after them.
+///
+/// An adapter may start anywhere in the read, including a partial overlap
+/// with the 3' end of at least `min_overlap` bases. The allowed mismatches
+/// scale down with the overlap length.
+#[derive(Debug, Clone)]
+pub struct AdapterTrimmer {
+    adapters: Vec<Adapter>,
+    hits: Vec<u64>,
+    max_mismatches: usize,
+    min_overlap: usize,
+}
+
+impl AdapterTrimmer {
+    /// Creates a trimmer with exact matching and a minimum overlap of 3.
+    pub fn new(adapters: Vec<Adapter>) -> Self {
+        let hits = vec![0; adapters.len()];
+        Self {
+            adapters,
+            hits,
+            max_mismatches: 0,
+            min_overlap: 3,
+        }
+    }
+
+    /// Sets the mismatches allowed for a full-length adapter match.
+    pub fn with_max_mismatches(mut self, max_mismatches: usize) -> Self {
+        self.max_mismatches = max_mismatches;
+        self
+    }
+
+    /// Sets the shortest partial adapter accepted at the 3' end.
+    pub fn with_min_overlap(mut self, min_overlap: usize) -> Self {
+        self.min_overlap = min_overlap.max(1);
+        self
+    }
+
+    /// Configured adapters.
+    pub fn adapters(&self) -> &[Adapter] {
+        &self.adapters
+    }
+
+    /// Number of reads trimmed per adapter, in configuration order.
+    pub fn hits(&self) -> &[u64] {
+        &self.hits
+    }
+
+    /// Trims `record` in place. Returns the index of the matched adapter.
+    pub fn trim(&mut self, record: &mut Record) -> Option<usize> {
+        let mut best: Option<(usize, usize)> = None;
+
+        for (index, adapter) in self.adapters.iter().enumerate() {
+            let limit = best.map_or(record.seq.len(), |(pos, _)| pos);
+            if let Some(pos) = self.find(&record.seq, &adapter.seq, limit) {
+                best = Some((pos, index));
+            }
+        }
+
+        let (pos, index) = best?;
+        record.seq.truncate(pos);
+        record.qual.truncate(pos);
+        self.hits[index] += 1;
+        Some(index)
+    }
+
+    /// Leftmost match of `adapter` starting before `limit`.
+    fn find(&self, seq: &[u8], adapter: &[u8], limit: usize) -> Option<usize> {
+        if adapter.is_empty() {
+            return None;
+        }
+        let min_overlap = self.min_overlap.min(adapter.len());
+        let last_start = seq.len().checked_sub(min_overlap)?;
+
+        (0..limit.min(last_start + 1)).find(|&start| {
+            let overlap = adapter.len().min(seq.len() - start);
+            let allowed = self.max_mismatches * overlap / adapter.len();
+            let mut mismatches = 0;
+            for (&a, &b) in seq[start..start + overlap].iter().zip(adapter) {
+                if !a.eq_ignore_ascii_case(&b) {
+                    mismatches += 1;
+                    if mismatches > allowed {
+                        return false;
+                    }
+                }
+            }
+            true
+        })
+    }
+}
+
+/// Counts occurrences of [`KNOWN_ADAPTERS`] to guess which one a library uses.
+#[derive(Debug, Clone)]
+pub struct AdapterDetector {
+    counts: Vec<u64>,
+}
+
+impl Default for AdapterDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdapterDetector {
+    /// Creates an empty detector.
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; KNOWN_ADAPTERS.len()],
+        }
+    }
+
+    /// Scans one read sequence.
+    pub fn observe(&mut self, seq: &[u8]) {
+        for (count, (_, adapter)) in self.counts.iter_mut().zip(KNOWN_ADAPTERS) {
+            let prefix = &adapter[..DETECTION_PREFIX.min(adapter.len())];
+            if memmem::find(seq, prefix).is_some() {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Number of reads containing each known adapter.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Most frequent known adapter, or `None` if none were seen.
+    pub fn adapter(&self) -> Option<Adapter> {
+        let (index, &count) = self
+            .counts
+            .iter()
+            .enumerate()
+            .max_by_key(|&(i, &count)| (count, std::cmp::Reverse(i)))?;
+        if count == 0 {
+            return None;
+        }
+        let (name, seq) = KNOWN_ADAPTERS[index];
+        Some(Adapter::new(name, seq))
+    }
+}
+
+/// Scans up to `max_records` reads and returns the most frequent known adapter.
+/// The scanned records are consumed from `reader`.
+pub fn detect_adapter<E: EventReader>(
+    reader: &mut RecordReader<E>,
+    max_records: usize,
+) -> Result<Option<Adapter>, ReaderError> {
+    let mut detector = AdapterDetector::new();
+    let mut record = Record::new();
+
+    for _ in 0..max_records {
+        if !reader.read_record(&mut record)? {
+            break;
+        }
+        detector.observe(&record.seq);
+    }
+
+    Ok(detector.adapter())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastqReader;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_full_and_partial_matches() {
+        let mut trimmer = AdapterTrimmer::new(vec![Adapter::new("universal", "AGATCGGAAGAGC")]);
+
+        let mut r = Record::from_parts(
+            b"read",
+            b"ACGTACGTAGATCGGAAGAGCTTTT",
+            b"IIIIIIIIIIIIIIIIIIIIIIIII",
+        );
+        assert_eq!(trimmer.trim(&mut r), Some(0));
+        assert_eq!(&r.seq, b"ACGTACGT");
+        assert_eq!(r.qual.len(), 8);
+
+        let mut r = Record::from_parts(b"read", b"ACGTACGTAGATC", b"ABCDEFGHIJKLM");
+        assert_eq!(trimmer.trim(&mut r), Some(0));
+        assert_eq!(&r.seq, b"ACGTACGT");
+        assert_eq!(&r.qual, b"ABCDEFGH");
+
+        let mut r = Record::from_parts(b"read", b"ACGTACGTCCCCC", b"IIIIIIIIIIIII");
+        assert_eq!(trimmer.trim(&mut r), None);
+        assert_eq!(r.seq.len(), 13);
+        assert_eq!(r.qual.len(), 13);
+
+        assert_eq!(trimmer.hits(), &[2]);
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let mut trimmer = AdapterTrimmer::new(vec![Adapter::new("universal", "AGATCGGAAGAGC")]);
+        let mut r = Record::from_parts(b"read", b"ACGTAGATCGGTAGAGC", b"ABCDEFGHIJKLMNOPQ");
+        assert_eq!(trimmer.trim(&mut r), None);
+
+        let mut trimmer = trimmer.with_max_mismatches(1);
+        assert_eq!(trimmer.trim(&mut r), Some(0));
+        assert_eq!(&r.seq, b"ACGT");
+        assert_eq!(&r.qual, b"ABCD");
+    }
+
+    #[test]
+    fn test_tie_goes_to_first_adapter() {
+        let mut trimmer = AdapterTrimmer::new(vec![
+            Adapter::new("long", "AGATCGGAAGAGC"),
+            Adapter::new("short", "AGATCGG"),
+        ]);
+        let mut r = Record::from_parts(b"read", b"AGATCGGAAGAGCTT", b"IIIIIIIIIIIIIII");
+        assert_eq!(trimmer.trim(&mut r), Some(0));
+        assert!(r.seq.is_empty());
+        assert!(r.qual.is_empty());
+
+        let mut r = Record::from_parts(b"read", b"CAGATCGGAAGAGCTT", b"IIIIIIIIIIIIIIII");
+        assert_eq!(trimmer.trim(&mut r), Some(0));
+        assert_eq!(trimmer.hits(), &[2, 0]);
+    }
+
+    #[test]
+    fn test_detect_adapter() {
+        let data = b"@r1\nACGTCTGTCTCTTATACACATCTGG\n+\nIIIIIIIIIIIIIIIIIIIIIIIII\n\
+                     @r2\nTTCTGTCTCTTATACACATCT\n+\nIIIIIIIIIIIIIIIIIIIII\n\
+                     @r3\nACGTACGT\n+\nIIIIIIII\n";
+        let mut reader = RecordReader::new(FastqReader::new(Cursor::new(&data[..])));
+        let adapter = detect_adapter(&mut reader, 10).unwrap().unwrap();
+        assert_eq!(adapter.name, "Nextera");
+
+        assert_eq!(AdapterDetector::new().adapter(), None);
+    }
+}
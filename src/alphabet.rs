@@ -0,0 +1,80 @@
+const DNA: [bool; 256] = build_table(b"ACGT");
+const DNA_N: [bool; 256] = build_table(b"ACGTN");
+const IUPAC: [bool; 256] = build_table(b"ACGTURYKMSWBDHVN");
+const RNA: [bool; 256] = build_table(b"ACGUN");
+const PROTEIN: [bool; 256] = build_table(b"ABCDEFGHIJKLMNOPQRSTUVWXYZ*");
+
+const fn build_table(symbols: &[u8]) -> [bool; 256] {
+    let mut table = [false; 256];
+    let mut i = 0;
+    while i < symbols.len() {
+        table[symbols[i] as usize] = true;
+        table[symbols[i].to_ascii_lowercase() as usize] = true;
+        i += 1;
+    }
+    table
+}
+
+/// Allowed sequence symbols. Matching is case-insensitive so soft-masked
+/// bases are accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    /// `ACGT` only.
+    Dna,
+    /// `ACGT` plus `N`.
+    DnaN,
+    /// Full IUPAC nucleotide codes, including `U`.
+    Iupac,
+    /// `ACGU` plus `N`.
+    Rna,
+    /// IUPAC amino acid codes plus `*` for stop.
+    Protein,
+}
+
+impl Alphabet {
+    #[inline]
+    fn table(self) -> &'static [bool; 256] {
+        match self {
+            Alphabet::Dna => &DNA,
+            Alphabet::DnaN => &DNA_N,
+            Alphabet::Iupac => &IUPAC,
+            Alphabet::Rna => &RNA,
+            Alphabet::Protein => &PROTEIN,
+        }
+    }
+
+    /// Returns `true` if `byte` belongs to the alphabet.
+    #[inline]
+    pub fn contains(self, byte: u8) -> bool {
+        self.table()[byte as usize]
+    }
+
+    /// Returns the position of the first byte outside the alphabet.
+    #[inline]
+    pub fn find_invalid(self, seq: &[u8]) -> Option<usize> {
+        let table = self.table();
+        seq.iter().position(|&b| !table[b as usize])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nucleotide_alphabets() {
+        assert_eq!(Alphabet::Dna.find_invalid(b"ACGTacgt"), None);
+        assert_eq!(Alphabet::Dna.find_invalid(b"ACGNT"), Some(3));
+        assert_eq!(Alphabet::DnaN.find_invalid(b"ACGNnT"), None);
+        assert_eq!(Alphabet::Iupac.find_invalid(b"RYKMSWBDHVN"), None);
+        assert_eq!(Alphabet::Rna.find_invalid(b"ACGU"), None);
+        assert_eq!(Alphabet::Rna.find_invalid(b"ACGT"), Some(3));
+    }
+
+    #[test]
+    fn test_protein_alphabet() {
+        assert_eq!(Alphabet::Protein.find_invalid(b"MKVLA*"), None);
+        assert_eq!(Alphabet::Protein.find_invalid(b"MK-VL"), Some(2));
+        assert!(!Alphabet::Protein.contains(b'>'));
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use crate::error::ReaderError;
+use crate::event::EventReader;
+use crate::illumina::CasavaComment;
+use crate::record::{Record, RecordReader};
+use crate::writer::FastqWriter;
+
+/// Name used for the output of reads that match no sample.
+pub const UNDETERMINED: &str = "Undetermined";
+
+/// Sample name and index sequence. Dual indexes are joined with `+`,
+/// matching the Casava header layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub name: String,
+    pub index: Vec<u8>,
+}
+
+/// Samples to demultiplex into.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SampleSheet {
+    pub samples: Vec<Sample>,
+}
+
+/// First-column names accepted in a header row.
+const HEADER_NAMES: [&str; 4] = ["sample", "sample_id", "sample_name", "name"];
+
+fn is_index(field: &str) -> bool {
+    !field.is_empty() && field.bytes().all(|b| b"ACGTNacgtn".contains(&b))
+}
+
+fn invalid_line(line_no: usize, line: &str, reason: &str) -> ReaderError {
+    ReaderError::InvalidFormat {
+        message: format!(
+            "Invalid sample sheet line {}: {reason}: '{}'",
+            line_no + 1,
+            line
+        ),
+    }
+}
+
+impl SampleSheet {
+    /// Parses `sample,index[,index2]` lines, comma- or tab-separated.
+    /// Blank lines and `#` comments are skipped, as is a header row on the
+    /// first line whose first column is `sample`, `sample_id`, `sample_name`
+    /// or `name`. Any other invalid line, a repeated sample name or a sample
+    /// named [`UNDETERMINED`] is an error.
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, ReaderError> {
+        let mut samples = Vec::new();
+        let mut names = HashSet::new();
+
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split([',', '\t']).map(str::trim).collect();
+            let valid = fields.len() >= 2 && fields[1..].iter().all(|f| is_index(f));
+            if !valid {
+                let header = HEADER_NAMES
+                    .iter()
+                    .any(|name| fields[0].eq_ignore_ascii_case(name));
+                if line_no == 0 && header {
+                    continue;
+                }
+                return Err(invalid_line(line_no, line, "expected sample and index"));
+            }
+            if fields[0] == UNDETERMINED || !names.insert(fields[0].to_string()) {
+                return Err(invalid_line(line_no, line, "duplicate sample name"));
+            }
+
+            let index = fields[1..].join("+").to_ascii_uppercase().into_bytes();
+            samples.push(Sample {
+                name: fields[0].to_string(),
+                index,
+            });
+        }
+
+        Ok(Self { samples })
+    }
+}
+
+/// Where the index of each read is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSource {
+    /// Index field of the Casava header comment.
+    Header,
+    /// First `length` bases of the read, which are removed from the read.
+    Inline { length: usize },
+}
+
+/// Read counts per sample after demultiplexing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DemuxSummary {
+    /// `(sample name, reads)` in sample sheet order.
+    pub samples: Vec<(String, u64)>,
+    pub undetermined: u64,
+}
+
+/// Routes reads to per-sample FASTQ writers by index sequence.
+///
+/// A read goes to the sample with the fewest mismatches, if that is within
+/// the limit and no other sample ties it; otherwise it is undetermined.
+pub struct Demultiplexer<W: Write> {
+    samples: Vec<Sample>,
+    writers: Vec<FastqWriter<W>>,
+    undetermined: FastqWriter<W>,
+    counts: Vec<u64>,
+    undetermined_count: u64,
+    source: IndexSource,
+    max_mismatches: usize,
+    index: Vec<u8>,
+}
+
+impl<W: Write> Demultiplexer<W> {
+    /// Creates a demultiplexer reading indexes from the header, with one
+    /// mismatch allowed. `open` is called once per sample name and once with
+    /// [`UNDETERMINED`].
+    pub fn new<F>(sheet: SampleSheet, mut open: F) -> io::Result<Self>
+    where
+        F: FnMut(&str) -> io::Result<W>,
+    {
+        let writers = sheet
+            .samples
+            .iter()
+            .map(|s| open(&s.name).map(FastqWriter::new))
+            .collect::<io::Result<Vec<_>>>()?;
+        let undetermined = FastqWriter::new(open(UNDETERMINED)?);
+
+        Ok(Self {
+            counts: vec![0; sheet.samples.len()],
+            samples: sheet.samples,
+            writers,
+            undetermined,
+            undetermined_count: 0,
+            source: IndexSource::Header,
+            max_mismatches: 1,
+            index: Vec::new(),
+        })
+    }
+
+    /// Sets where indexes are read from.
+    pub fn with_index_source(mut self, source: IndexSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets the mismatches allowed between read and sample index.
+    pub fn with_max_mismatches(mut self, max_mismatches: usize) -> Self {
+        self.max_mismatches = max_mismatches;
+        self
+    }
+
+    /// Returns the sample index `index` matches, or `None` if undetermined.
+    pub fn classify(&self, index: &[u8]) -> Option<usize> {
+        let mut best: Option<(usize, usize)> = None;
+        let mut tied = false;
+
+        for (i, sample) in self.samples.iter().enumerate() {
+            if sample.index.len() != index.len() {
+                continue;
+            }
+            let distance = sample
+                .index
+                .iter()
+                .zip(index)
+                .filter(|&(a, b)| !a.eq_ignore_ascii_case(b) || *b == b'N' || *b == b'n')
+                .count();
+            if distance > self.max_mismatches {
+                continue;
+            }
+            match best {
+                Some((_, d)) if distance > d => {}
+                Some((_, d)) if distance == d => tied = true,
+                _ => {
+                    best = Some((i, distance));
+                    tied = false;
+                }
+            }
+        }
+
+        if tied { None } else { best.map(|(i, _)| i) }
+    }
+
+    /// Routes one record, removing an inline index first.
+    /// Returns the matched sample, or `None` if written as undetermined.
+    pub fn demultiplex_record(&mut self, record: &mut Record) -> io::Result<Option<usize>> {
+        self.index.clear();
+        match self.source {
+            IndexSource::Header => {
+                if let Some(comment) = record.description().and_then(CasavaComment::parse) {
+                    self.index.extend_from_slice(comment.index);
+                }
+            }
+            IndexSource::Inline { length } => {
+                let length = length.min(record.seq.len());
+                self.index.extend(record.seq.drain(..length));
+                if record.qual.len() >= length {
+                    record.qual.drain(..length);
+                }
+            }
+        }
+
+        let sample = self.classify(&self.index);
+        match sample {
+            Some(i) => {
+                self.writers[i].write_record(record)?;
+                self.counts[i] += 1;
+            }
+            None => {
+                self.undetermined.write_record(record)?;
+                self.undetermined_count += 1;
+            }
+        }
+        Ok(sample)
+    }
+
+    /// Demultiplexes every remaining record of `reader`.
+    pub fn run<E: EventReader>(&mut self, reader: &mut RecordReader<E>) -> Result<(), ReaderError> {
+        let mut record = Record::new();
+        while reader.read_record(&mut record)? {
+            self.demultiplex_record(&mut record)?;
+        }
+        Ok(())
+    }
+
+    /// Read counts so far.
+    pub fn summary(&self) -> DemuxSummary {
+        DemuxSummary {
+            samples: self
+                .samples
+                .iter()
+                .zip(&self.counts)
+                .map(|(s, &n)| (s.name.clone(), n))
+                .collect(),
+            undetermined: self.undetermined_count,
+        }
+    }
+
+    /// Flushes all outputs and returns the final counts.
+    pub fn finish(mut self) -> io::Result<DemuxSummary> {
+        for writer in &mut self.writers {
+            writer.flush()?;
+        }
+        self.undetermined.flush()?;
+        Ok(self.summary())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastqReader;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    /// Writer that shares its output so tests can inspect it after flushing.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn demux(sheet: &str) -> (Demultiplexer<SharedBuf>, HashMap<String, SharedBuf>) {
+        let sheet = SampleSheet::parse(sheet.as_bytes()).unwrap();
+        let mut outputs = HashMap::new();
+        let demux = Demultiplexer::new(sheet, |name| {
+            let buf = SharedBuf::default();
+            outputs.insert(name.to_string(), buf.clone());
+            Ok(buf)
+        })
+        .unwrap();
+        (demux, outputs)
+    }
+
+    #[test]
+    fn test_sample_sheet() {
+        let sheet = SampleSheet::parse(
+            &b"Sample_ID,index,index2\n# note\nS1,acgt,TTTT\n\nS2\tGGGG\tCCCC\n"[..],
+        )
+        .unwrap();
+        assert_eq!(sheet.samples.len(), 2);
+        assert_eq!(sheet.samples[0].index, b"ACGT+TTTT");
+        assert_eq!(sheet.samples[1].name, "S2");
+
+        assert!(SampleSheet::parse(&b"S1,ACGT\nS2,XYZ\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_sample_sheet_errors() {
+        // A typo in the first sample is not mistaken for a header row.
+        assert!(SampleSheet::parse(&b"S1,ACGX\nS2,GGGG\n"[..]).is_err());
+        // Headers are only recognised on the first line.
+        assert!(SampleSheet::parse(&b"# note\nsample,barcode\nS1,ACGT\n"[..]).is_err());
+        assert!(SampleSheet::parse(&b"S1,ACGT\nS2,GGGG\nS1,TTTT\n"[..]).is_err());
+        assert!(SampleSheet::parse(&b"Undetermined,ACGT\n"[..]).is_err());
+
+        let sheet = SampleSheet::parse(&b"sample,barcode\nS1,ACGT\n"[..]).unwrap();
+        assert_eq!(sheet.samples.len(), 1);
+    }
+
+    #[test]
+    fn test_header_demultiplexing() {
+        let (demux, outputs) = demux("S1,AAAA\nS2,CCCC\nS3,CCCA\n");
+        let mut demux = demux.with_max_mismatches(1);
+        let data = b"@r1 1:N:0:AAAT\nACGT\n+\nIIII\n\
+                     @r2 1:N:0:CCCC\nACGT\n+\nIIII\n\
+                     @r3 1:N:0:CCCG\nACGT\n+\nIIII\n\
+                     @r4 1:N:0:GGGG\nACGT\n+\nIIII\n";
+        let mut reader = RecordReader::new(FastqReader::new(Cursor::new(&data[..])));
+        demux.run(&mut reader).unwrap();
+        let summary = demux.finish().unwrap();
+
+        assert_eq!(summary.samples[0], ("S1".to_string(), 1));
+        assert_eq!(summary.samples[1], ("S2".to_string(), 1));
+        assert_eq!(summary.samples[2], ("S3".to_string(), 0));
+        assert_eq!(summary.undetermined, 2);
+        assert_eq!(
+            &*outputs["S1"].0.borrow(),
+            b"@r1 1:N:0:AAAT\nACGT\n+\nIIII\n"
+        );
+        assert!(outputs[UNDETERMINED].0.borrow().starts_with(b"@r3"));
+    }
+
+    #[test]
+    fn test_inline_demultiplexing() {
+        let (demux, outputs) = demux("S1,AC\nS2,GT\n");
+        let mut demux = demux
+            .with_index_source(IndexSource::Inline { length: 2 })
+            .with_max_mismatches(0);
+        let mut record = Record::from_parts(b"r1", b"GTAAAA", b"##IIII");
+
+        assert_eq!(demux.demultiplex_record(&mut record).unwrap(), Some(1));
+        demux.finish().unwrap();
+        assert_eq!(&*outputs["S2"].0.borrow(), b"@r1\nAAAA\n+\nIIII\n");
+    }
+}
@@ -9,4 +9,27 @@ pub enum ReaderError {
 
     #[error("Invalid format: {message}")]
     InvalidFormat { message: String },
+
+    #[error(
+        "Invalid sequence byte '{}' (0x{byte:02x}) at offset {offset} of record {record}",
+        *.byte as char
+    )]
+    InvalidSequence {
+        byte: u8,
+        offset: usize,
+        record: usize,
+    },
+
+    #[error(
+        "Invalid quality character '{}' (0x{byte:02x}) at offset {offset} of record {record}",
+        *.byte as char
+    )]
+    InvalidQuality {
+        byte: u8,
+        offset: usize,
+        record: usize,
+    },
+
+    #[error("Paired reads out of sync at record {record}: {message}")]
+    PairMismatch { record: usize, message: String },
 }
@@ -1,3 +1,5 @@
+use crate::error::ReaderError;
+
 /// Parsing event. References are valid until the next `next_event()` call.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event<'a> {
@@ -11,3 +13,9 @@ pub enum Event<'a> {
     /// Phred quality scores chunk, ASCII-encoded (FASTQ only).
     QualChunk(&'a [u8]),
 }
+
+/// Source of parsing events.
+pub trait EventReader {
+    /// Returns the next event, or `None` at EOF.
+    fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>>;
+}
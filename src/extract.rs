@@ -0,0 +1,355 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, Write};
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::revcomp::reverse_complement_in_place;
+use crate::util::{parse_num, split_name};
+use crate::writer::FastaWriter;
+
+/// Region of a reference sequence, 0-based half-open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval {
+    pub chrom: Vec<u8>,
+    pub start: usize,
+    pub end: usize,
+    /// BED name column or GFF `ID`/`Name` attribute.
+    pub name: Option<Vec<u8>>,
+    /// `true` on the `-` strand.
+    pub reverse: bool,
+}
+
+fn invalid_line(kind: &str, line_no: usize, line: &str) -> ReaderError {
+    ReaderError::InvalidFormat {
+        message: format!("Invalid {kind} line {}: '{}'", line_no + 1, line),
+    }
+}
+
+fn is_header(line: &str) -> bool {
+    line.is_empty()
+        || line.starts_with('#')
+        || line.starts_with("track")
+        || line.starts_with("browser")
+}
+
+/// Parses BED intervals (`chrom start end [name [score [strand]]]`).
+/// Blank, `#`, `track` and `browser` lines are skipped.
+pub fn parse_bed<R: BufRead>(reader: R) -> Result<Vec<Interval>, ReaderError> {
+    let mut intervals = Vec::new();
+
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        if is_header(line) {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (Some(start), Some(end)) = (
+            fields.get(1).and_then(|f| parse_num::<usize>(f.as_bytes())),
+            fields.get(2).and_then(|f| parse_num::<usize>(f.as_bytes())),
+        ) else {
+            return Err(invalid_line("BED", line_no, line));
+        };
+        if start > end {
+            return Err(invalid_line("BED", line_no, line));
+        }
+
+        intervals.push(Interval {
+            chrom: fields[0].as_bytes().to_vec(),
+            start,
+            end,
+            name: fields
+                .get(3)
+                .filter(|f| !f.is_empty() && **f != ".")
+                .map(|f| f.as_bytes().to_vec()),
+            reverse: fields.get(5) == Some(&"-"),
+        });
+    }
+
+    Ok(intervals)
+}
+
+/// Parses GFF3 features, converting 1-based inclusive coordinates. Comment
+/// lines are skipped and parsing stops at a `##FASTA` section.
+pub fn parse_gff<R: BufRead>(reader: R) -> Result<Vec<Interval>, ReaderError> {
+    let mut intervals = Vec::new();
+
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        if line.starts_with("##FASTA") {
+            break;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 9 {
+            return Err(invalid_line("GFF", line_no, line));
+        }
+        let (Some(start), Some(end)) = (
+            parse_num::<usize>(fields[3].as_bytes()),
+            parse_num::<usize>(fields[4].as_bytes()),
+        ) else {
+            return Err(invalid_line("GFF", line_no, line));
+        };
+        if start == 0 || start > end + 1 {
+            return Err(invalid_line("GFF", line_no, line));
+        }
+
+        let attribute = |key: &str| {
+            fields[8].split(';').find_map(|attr| {
+                let (k, v) = attr.trim().split_once('=')?;
+                (k == key).then(|| v.as_bytes().to_vec())
+            })
+        };
+        intervals.push(Interval {
+            chrom: fields[0].as_bytes().to_vec(),
+            start: start - 1,
+            end,
+            name: attribute("ID").or_else(|| attribute("Name")),
+            reverse: fields[6] == "-",
+        });
+    }
+
+    Ok(intervals)
+}
+
+/// Extraction counts from [`IntervalExtractor::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractSummary {
+    pub written: u64,
+    /// Intervals on sequences not in the FASTA or past their end.
+    pub skipped: u64,
+}
+
+/// Extracts intervals from a FASTA stream in one pass, without an index.
+///
+/// Intervals need not be sorted. Each is buffered only while the stream is
+/// inside it, and written, reverse complemented on the `-` strand, as soon
+/// as its end is reached; output is therefore ordered by sequence, then by
+/// end and start position. Records are named by the interval name, or
+/// `chrom:start-end`, with `(-)` appended on the reverse strand.
+#[derive(Debug, Clone)]
+pub struct IntervalExtractor {
+    by_chrom: HashMap<Vec<u8>, Vec<Interval>>,
+    header: Vec<u8>,
+}
+
+impl IntervalExtractor {
+    /// Creates an extractor for `intervals`.
+    pub fn new(intervals: Vec<Interval>) -> Self {
+        let mut by_chrom: HashMap<Vec<u8>, Vec<Interval>> = HashMap::new();
+        for interval in intervals {
+            by_chrom
+                .entry(interval.chrom.clone())
+                .or_default()
+                .push(interval);
+        }
+        for list in by_chrom.values_mut() {
+            list.sort_by_key(|interval| (interval.start, interval.end));
+        }
+        Self {
+            by_chrom,
+            header: Vec::new(),
+        }
+    }
+
+    /// Streams `reader` once, writing every interval to `writer`.
+    pub fn run<E: EventReader, W: Write>(
+        &mut self,
+        reader: &mut E,
+        writer: &mut FastaWriter<W>,
+    ) -> Result<ExtractSummary, ReaderError> {
+        let mut summary = ExtractSummary::default();
+        let mut id = Vec::new();
+        let mut pending: Option<VecDeque<Interval>> = None;
+        let mut active: Vec<(Interval, Vec<u8>)> = Vec::new();
+        let mut done = Vec::new();
+        let mut pos = 0;
+
+        loop {
+            let event = match reader.next_event() {
+                Some(event) => Some(event?),
+                None => None,
+            };
+
+            match event {
+                Some(Event::IdChunk(chunk)) => id.extend_from_slice(chunk),
+                Some(Event::SeqChunk(chunk)) => {
+                    let pending = pending.get_or_insert_with(|| self.take_intervals(&id));
+                    let end = pos + chunk.len();
+                    while pending.front().is_some_and(|i| i.start < end) {
+                        let interval = pending.pop_front().unwrap();
+                        let len = interval.end - interval.start;
+                        active.push((interval, Vec::with_capacity(len)));
+                    }
+
+                    for (interval, seq) in &mut active {
+                        let from = interval.start.max(pos);
+                        let to = interval.end.min(end);
+                        if from < to {
+                            seq.extend_from_slice(&chunk[from - pos..to - pos]);
+                        }
+                    }
+
+                    done.extend(active.extract_if(.., |(interval, _)| interval.end <= end));
+                    done.sort_by_key(|(interval, _)| (interval.end, interval.start));
+                    for (interval, mut seq) in done.drain(..) {
+                        self.write(writer, &interval, &mut seq)?;
+                        summary.written += 1;
+                    }
+                    pos = end;
+                }
+                Some(Event::QualChunk(_)) => {}
+                Some(Event::NextRecord) | None => {
+                    let pending = pending.take().unwrap_or_else(|| self.take_intervals(&id));
+                    let unfinished = active.drain(..).map(|(interval, _)| interval);
+                    for interval in pending.into_iter().chain(unfinished) {
+                        // Only empty intervals at the very end can still be satisfied.
+                        if interval.end <= pos && interval.start == interval.end {
+                            self.write(writer, &interval, &mut Vec::new())?;
+                            summary.written += 1;
+                        } else {
+                            summary.skipped += 1;
+                        }
+                    }
+                    id.clear();
+                    pos = 0;
+
+                    if event.is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        summary.skipped += self.by_chrom.values().map(|l| l.len() as u64).sum::<u64>();
+        self.by_chrom.clear();
+        writer.flush()?;
+        Ok(summary)
+    }
+
+    /// Removes and returns the intervals of the record with header `id`.
+    fn take_intervals(&mut self, id: &[u8]) -> VecDeque<Interval> {
+        self.by_chrom
+            .remove(split_name(id).0)
+            .unwrap_or_default()
+            .into()
+    }
+
+    fn write<W: Write>(
+        &mut self,
+        writer: &mut FastaWriter<W>,
+        interval: &Interval,
+        seq: &mut [u8],
+    ) -> io::Result<()> {
+        self.header.clear();
+        match &interval.name {
+            Some(name) => self.header.extend_from_slice(name),
+            None => {
+                self.header.extend_from_slice(&interval.chrom);
+                write!(self.header, ":{}-{}", interval.start, interval.end)?;
+            }
+        }
+        if interval.reverse {
+            reverse_complement_in_place(seq);
+            self.header.extend_from_slice(b"(-)");
+        }
+        writer.write_parts(&self.header, seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastaReader;
+
+    const FASTA: &[u8] = b">chr1 test\nACGTACGTAC\nGGGGTTTTAA\n>chr2\nTTTTCCCC\n>chr3\nAC\n";
+
+    fn interval(chrom: &[u8], start: usize, end: usize, reverse: bool) -> Interval {
+        Interval {
+            chrom: chrom.to_vec(),
+            start,
+            end,
+            name: None,
+            reverse,
+        }
+    }
+
+    fn extract(intervals: Vec<Interval>, capacity: usize) -> (ExtractSummary, String) {
+        let mut writer = FastaWriter::new(Vec::new());
+        let summary = IntervalExtractor::new(intervals)
+            .run(
+                &mut FastaReader::with_capacity(capacity, FASTA),
+                &mut writer,
+            )
+            .unwrap();
+        (
+            summary,
+            String::from_utf8(writer.into_inner().unwrap()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse_bed() {
+        let bed = "track name=x\n# comment\nchr1\t0\t4\ngene1\t0\t+\nchr2\t2\t6\tfeat\t0\t-\n";
+        assert!(parse_bed(bed.as_bytes()).is_err());
+
+        let bed = "track name=x\n# comment\nchr1\t0\t4\nchr2\t2\t6\tfeat\t0\t-\n";
+        let intervals = parse_bed(bed.as_bytes()).unwrap();
+        assert_eq!(intervals[0], interval(b"chr1", 0, 4, false));
+        assert_eq!(intervals[1].name.as_deref(), Some(&b"feat"[..]));
+        assert!(intervals[1].reverse);
+    }
+
+    #[test]
+    fn test_parse_gff() {
+        let gff = "##gff-version 3\n\
+                   chr1\tsrc\tgene\t3\t8\t.\t-\t.\tID=g1;Name=abc\n\
+                   chr2\tsrc\tCDS\t1\t4\t.\t+\t0\tName=cds1\n\
+                   ##FASTA\n>chr1\nACGT\n";
+        let intervals = parse_gff(gff.as_bytes()).unwrap();
+        assert_eq!(intervals.len(), 2);
+        assert_eq!((intervals[0].start, intervals[0].end), (2, 8));
+        assert_eq!(intervals[0].name.as_deref(), Some(&b"g1"[..]));
+        assert!(intervals[0].reverse);
+        assert_eq!(intervals[1].name.as_deref(), Some(&b"cds1"[..]));
+
+        assert!(parse_gff("chr1\tsrc\tgene\t0\t8\t.\t+\t.\t.\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_single_pass_extraction() {
+        let mut named = interval(b"chr1", 8, 12, false);
+        named.name = Some(b"across_lines".to_vec());
+        let intervals = vec![
+            interval(b"chr2", 2, 6, true),
+            interval(b"chr1", 0, 20, false),
+            named,
+            interval(b"chr1", 2, 5, true),
+            interval(b"chrX", 0, 1, false),
+            interval(b"chr3", 1, 5, false),
+            interval(b"chr3", 2, 2, false),
+        ];
+
+        let expected = ">chr1:2-5(-)\nTAC\n\
+                        >across_lines\nACGG\n\
+                        >chr1:0-20\nACGTACGTACGGGGTTTTAA\n\
+                        >chr2:2-6(-)\nGGAA\n\
+                        >chr3:2-2\n\n";
+        for capacity in [1, 4, 64] {
+            let (summary, out) = extract(intervals.clone(), capacity);
+            assert_eq!(out, expected);
+            assert_eq!(
+                summary,
+                ExtractSummary {
+                    written: 5,
+                    skipped: 2
+                }
+            );
+        }
+    }
+}
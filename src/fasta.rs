@@ -1,9 +1,11 @@
 use std::io::{BufRead, BufReader, Read};
+use std::ops::Range;
 
-use memchr::{memchr, memchr3};
+use memchr::{memchr, memchr2};
 
+use crate::alphabet::Alphabet;
 use crate::error::ReaderError;
-use crate::event::Event;
+use crate::event::{Event, EventReader};
 
 const DEFAULT_BUFFER_SIZE: usize = 128 * 1024;
 
@@ -19,6 +21,14 @@ pub struct FastaReader<R> {
     reader: BufReader<R>,
     pending_consume: usize,
     state: State,
+    line_start: bool,
+    record_index: usize,
+    seq_len: usize,
+    alphabet: Option<Alphabet>,
+    track_mask: bool,
+    masked: Vec<Range<usize>>,
+    uppercase: bool,
+    seq_buf: Vec<u8>,
 }
 
 impl<R: Read> FastaReader<R> {
@@ -33,6 +43,58 @@ impl<R: Read> FastaReader<R> {
             reader: BufReader::with_capacity(capacity, reader),
             pending_consume: 0,
             state: State::Start,
+            line_start: true,
+            record_index: 0,
+            seq_len: 0,
+            alphabet: None,
+            track_mask: false,
+            masked: Vec::new(),
+            uppercase: false,
+            seq_buf: Vec::new(),
+        }
+    }
+
+    /// Validates every `SeqChunk` against `alphabet`.
+    pub fn with_alphabet(mut self, alphabet: Alphabet) -> Self {
+        self.alphabet = Some(alphabet);
+        self
+    }
+
+    /// Records soft-masked (lowercase) intervals, see
+    /// [`FastaReader::masked_intervals`].
+    pub fn with_mask_intervals(mut self) -> Self {
+        self.track_mask = true;
+        self
+    }
+
+    /// Uppercases every `SeqChunk`. Combine with
+    /// [`FastaReader::with_mask_intervals`] to keep the mask information.
+    pub fn with_uppercase(mut self) -> Self {
+        self.uppercase = true;
+        self
+    }
+
+    /// Lowercase intervals of the current record as half-open offsets, merged
+    /// across lines and chunks. Complete when `NextRecord` or the end of input
+    /// is reached; cleared when the next header is read. Empty unless
+    /// [`FastaReader::with_mask_intervals`] is set.
+    pub fn masked_intervals(&self) -> &[Range<usize>] {
+        &self.masked
+    }
+
+    fn record_mask(&mut self, slice: &[u8], offset: usize) {
+        let mut i = 0;
+        while let Some(start) = slice[i..].iter().position(u8::is_ascii_lowercase) {
+            let start = i + start;
+            let end = slice[start..]
+                .iter()
+                .position(|b| !b.is_ascii_lowercase())
+                .map_or(slice.len(), |len| start + len);
+            match self.masked.last_mut() {
+                Some(last) if last.end == offset + start => last.end = offset + end,
+                _ => self.masked.push(offset + start..offset + end),
+            }
+            i = end;
         }
     }
 
@@ -44,6 +106,7 @@ impl<R: Read> FastaReader<R> {
                 self.pending_consume = 0;
             }
 
+            #[allow(clippy::redundant_guards)]
             let buf = match self.reader.fill_buf() {
                 Ok(b) if b.is_empty() => return None,
                 Ok(b) => b,
@@ -84,6 +147,7 @@ impl<R: Read> FastaReader<R> {
                 }
 
                 State::Id => {
+                    self.masked.clear();
                     if let Some(newline_pos) = memchr(b'\n', buf) {
                         let end = if newline_pos > 0 && buf[newline_pos - 1] == b'\r' {
                             newline_pos - 1
@@ -92,6 +156,7 @@ impl<R: Read> FastaReader<R> {
                         };
 
                         self.state = State::Sequence;
+                        self.line_start = true;
                         self.pending_consume = newline_pos + 1;
 
                         if end > 0 {
@@ -112,26 +177,48 @@ impl<R: Read> FastaReader<R> {
 
                     if first_byte == b'\n' {
                         self.pending_consume = 1;
+                        self.line_start = true;
                         continue;
                     }
                     if first_byte == b'\r' {
                         self.pending_consume = if buf_len > 1 && buf[1] == b'\n' { 2 } else { 1 };
+                        self.line_start = true;
                         continue;
                     }
-                    if first_byte == b'>' {
+                    // '>' only starts a record at the beginning of a line
+                    if first_byte == b'>' && self.line_start {
                         self.state = State::Id;
                         self.pending_consume = 1;
+                        self.record_index += 1;
+                        self.seq_len = 0;
                         return Some(Ok(Event::NextRecord));
                     }
 
-                    let chunk_end = memchr3(b'\n', b'\r', b'>', buf).unwrap_or(buf_len);
-                    if chunk_end == 0 {
-                        self.pending_consume = 1;
-                        continue;
-                    }
+                    let chunk_end = memchr2(b'\n', b'\r', buf).unwrap_or(buf_len);
+                    let offset = self.seq_len;
 
+                    self.line_start = false;
                     self.pending_consume = chunk_end;
+                    self.seq_len += chunk_end;
                     let slice = unsafe { std::slice::from_raw_parts(buf_ptr, chunk_end) };
+
+                    if let Some(pos) = self.alphabet.and_then(|a| a.find_invalid(slice)) {
+                        return Some(Err(ReaderError::InvalidSequence {
+                            byte: slice[pos],
+                            offset: offset + pos,
+                            record: self.record_index,
+                        }));
+                    }
+
+                    if self.track_mask {
+                        self.record_mask(slice, offset);
+                    }
+                    if self.uppercase {
+                        self.seq_buf.clear();
+                        self.seq_buf.extend(slice.iter().map(u8::to_ascii_uppercase));
+                        return Some(Ok(Event::SeqChunk(&self.seq_buf)));
+                    }
+
                     return Some(Ok(Event::SeqChunk(slice)));
                 }
             }
@@ -139,6 +226,12 @@ impl<R: Read> FastaReader<R> {
     }
 }
 
+impl<R: Read> EventReader for FastaReader<R> {
+    fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>> {
+        FastaReader::next_event(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,4 +293,64 @@ mod tests {
         assert_eq!(&id, b"seq1");
         assert_eq!(&seq, b"ACGTACGTACGT");
     }
+
+    #[test]
+    fn test_gt_inside_line_is_sequence() {
+        let data = b">seq1\nAC>GT\n>seq2\nTT\n";
+        let mut reader = FastaReader::with_capacity(3, Cursor::new(&data[..]));
+
+        let mut seq = Vec::new();
+        let mut records = 1;
+        while let Some(event) = reader.next_event() {
+            match event.unwrap() {
+                Event::NextRecord => records += 1,
+                Event::SeqChunk(chunk) if records == 1 => seq.extend_from_slice(chunk),
+                _ => {}
+            }
+        }
+
+        assert_eq!(records, 2);
+        assert_eq!(&seq, b"AC>GT");
+    }
+
+    #[test]
+    fn test_alphabet_validation() {
+        let data = b">seq1\nACGT\n>seq2\nACGT\nAC>T\n";
+        let mut reader = FastaReader::new(Cursor::new(&data[..])).with_alphabet(Alphabet::Dna);
+
+        let err = loop {
+            match reader.next_event() {
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => break e,
+                None => panic!("Expected validation error"),
+            }
+        };
+
+        assert!(matches!(
+            err,
+            ReaderError::InvalidSequence { byte: b'>', offset: 6, record: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_mask_intervals() {
+        let data = b">seq1\nACgt\nnnAAaC\nc\n>seq2\nACGT\n>seq3\naa\n";
+        let mut reader = FastaReader::with_capacity(3, Cursor::new(&data[..]))
+            .with_mask_intervals()
+            .with_uppercase();
+
+        let mut seq = Vec::new();
+        let mut masks = Vec::new();
+        while let Some(event) = reader.next_event() {
+            match event.unwrap() {
+                Event::SeqChunk(chunk) => seq.extend_from_slice(chunk),
+                Event::NextRecord => masks.push(reader.masked_intervals().to_vec()),
+                _ => {}
+            }
+        }
+        masks.push(reader.masked_intervals().to_vec());
+
+        assert_eq!(&seq, b"ACGTNNAAACCACGTAA");
+        assert_eq!(masks, vec![vec![2..6, 8..9, 10..11], vec![], vec![0..2]]);
+    }
 }
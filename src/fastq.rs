@@ -2,8 +2,10 @@ use std::io::{BufRead, BufReader, Read};
 
 use memchr::{memchr, memchr2, memchr3};
 
+use crate::alphabet::Alphabet;
 use crate::error::ReaderError;
-use crate::event::Event;
+use crate::event::{Event, EventReader};
+use crate::quality::QualityEncoding;
 
 const DEFAULT_BUFFER_SIZE: usize = 128 * 1024;
 
@@ -24,6 +26,12 @@ pub struct FastqReader<R> {
     seq_len: usize,
     qual_len: usize,
     first_record: bool,
+    record_index: usize,
+    alphabet: Option<Alphabet>,
+    quality_encoding: Option<QualityEncoding>,
+    phred33_output: bool,
+    rescale: Option<Box<[u8; 256]>>,
+    qual_buf: Vec<u8>,
 }
 
 impl<R: Read> FastqReader<R> {
@@ -41,9 +49,45 @@ impl<R: Read> FastqReader<R> {
             seq_len: 0,
             qual_len: 0,
             first_record: true,
+            record_index: 0,
+            alphabet: None,
+            quality_encoding: None,
+            phred33_output: false,
+            rescale: None,
+            qual_buf: Vec::new(),
         }
     }
 
+    /// Validates every `SeqChunk` against `alphabet`.
+    pub fn with_alphabet(mut self, alphabet: Alphabet) -> Self {
+        self.alphabet = Some(alphabet);
+        self
+    }
+
+    /// Validates every `QualChunk` against the character range of `encoding`.
+    pub fn with_quality_encoding(mut self, encoding: QualityEncoding) -> Self {
+        self.quality_encoding = Some(encoding);
+        self.update_rescale();
+        self
+    }
+
+    /// Rescales `QualChunk` output to Phred+33.
+    /// Has no effect unless a quality encoding is set.
+    pub fn with_phred33_output(mut self) -> Self {
+        self.phred33_output = true;
+        self.update_rescale();
+        self
+    }
+
+    fn update_rescale(&mut self) {
+        self.rescale = match self.quality_encoding {
+            Some(encoding) if self.phred33_output && encoding != QualityEncoding::Phred33 => {
+                Some(Box::new(encoding.phred33_table()))
+            }
+            _ => None,
+        };
+    }
+
     /// Returns the next event, or `None` at EOF.
     pub fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>> {
         loop {
@@ -52,6 +96,7 @@ impl<R: Read> FastqReader<R> {
                 self.pending_consume = 0;
             }
 
+            #[allow(clippy::redundant_guards)]
             let buf = match self.reader.fill_buf() {
                 Ok(b) if b.is_empty() => return None,
                 Ok(b) => b,
@@ -77,6 +122,7 @@ impl<R: Read> FastqReader<R> {
                                 if is_first {
                                     continue; // First record - no event
                                 }
+                                self.record_index += 1;
                                 return Some(Ok(Event::NextRecord));
                             } else {
                                 return Some(Err(ReaderError::InvalidFormat {
@@ -149,9 +195,19 @@ impl<R: Read> FastqReader<R> {
                         continue;
                     }
 
+                    let offset = self.seq_len;
                     self.pending_consume = chunk_end;
                     self.seq_len += chunk_end;
                     let slice = unsafe { std::slice::from_raw_parts(buf_ptr, chunk_end) };
+
+                    if let Some(pos) = self.alphabet.and_then(|a| a.find_invalid(slice)) {
+                        return Some(Err(ReaderError::InvalidSequence {
+                            byte: slice[pos],
+                            offset: offset + pos,
+                            record: self.record_index,
+                        }));
+                    }
+
                     return Some(Ok(Event::SeqChunk(slice)));
                 }
 
@@ -198,6 +254,7 @@ impl<R: Read> FastqReader<R> {
                         continue;
                     }
 
+                    let offset = self.qual_len;
                     self.pending_consume = chunk_end;
                     self.qual_len += chunk_end;
                     let slice = unsafe { std::slice::from_raw_parts(buf_ptr, chunk_end) };
@@ -206,6 +263,20 @@ impl<R: Read> FastqReader<R> {
                         self.state = State::Start;
                     }
 
+                    if let Some(pos) = self.quality_encoding.and_then(|e| e.find_invalid(slice)) {
+                        return Some(Err(ReaderError::InvalidQuality {
+                            byte: slice[pos],
+                            offset: offset + pos,
+                            record: self.record_index,
+                        }));
+                    }
+
+                    if let Some(table) = &self.rescale {
+                        self.qual_buf.clear();
+                        self.qual_buf.extend(slice.iter().map(|&b| table[b as usize]));
+                        return Some(Ok(Event::QualChunk(&self.qual_buf)));
+                    }
+
                     return Some(Ok(Event::QualChunk(slice)));
                 }
             }
@@ -213,6 +284,12 @@ impl<R: Read> FastqReader<R> {
     }
 }
 
+impl<R: Read> EventReader for FastqReader<R> {
+    fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>> {
+        FastqReader::next_event(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,4 +356,60 @@ mod tests {
         assert_eq!(&seq, b"ACGTACGT");
         assert_eq!(&qual, b"IIIIIIII");
     }
+
+    #[test]
+    fn test_alphabet_validation() {
+        let data = b"@read1\nACGT\n+\nIIII\n@read2\nACNT\n+\nIIII\n";
+        let mut reader = FastqReader::new(Cursor::new(&data[..])).with_alphabet(Alphabet::Dna);
+
+        let err = loop {
+            match reader.next_event() {
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => break e,
+                None => panic!("Expected validation error"),
+            }
+        };
+
+        assert!(matches!(
+            err,
+            ReaderError::InvalidSequence { byte: b'N', offset: 2, record: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_quality_validation() {
+        let data = b"@read1\nACGT\n+\nhhhh\n@read2\nACGT\n+\nhh5h\n";
+        let mut reader = FastqReader::new(Cursor::new(&data[..]))
+            .with_quality_encoding(QualityEncoding::Phred64);
+
+        let err = loop {
+            match reader.next_event() {
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => break e,
+                None => panic!("Expected validation error"),
+            }
+        };
+
+        assert!(matches!(
+            err,
+            ReaderError::InvalidQuality { byte: b'5', offset: 2, record: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_phred64_rescaling() {
+        let data = b"@read1\nACGT\n+\n@Thh\n";
+        let mut reader = FastqReader::with_capacity(4, Cursor::new(&data[..]))
+            .with_quality_encoding(QualityEncoding::Phred64)
+            .with_phred33_output();
+
+        let mut qual = Vec::new();
+        while let Some(event) = reader.next_event() {
+            if let Event::QualChunk(chunk) = event.unwrap() {
+                qual.extend_from_slice(chunk);
+            }
+        }
+
+        assert_eq!(&qual, b"!5II");
+    }
 }
@@ -0,0 +1,299 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+#[cfg(feature = "regex")]
+use regex::bytes::Regex;
+
+use crate::error::ReaderError;
+use crate::event::EventReader;
+use crate::quality::{QualityEncoding, QualityStats};
+use crate::record::{Record, RecordReader};
+use crate::sam_tag::SamTag;
+use crate::writer::FastqWriter;
+
+/// DUST low-complexity score of a sequence: `sum(c * (c - 1) / 2) / (l - 1)`
+/// over the counts `c` of its `l` overlapping triplets. Triplets containing
+/// non-ACGT bases are skipped. Homopolymers score highest.
+pub fn dust_score(seq: &[u8]) -> f64 {
+    let mut counts = [0u32; 64];
+    let mut triplets = 0u32;
+    let mut code = 0usize;
+    let mut valid = 0;
+
+    for &b in seq {
+        let bits = match b {
+            b'A' | b'a' => 0,
+            b'C' | b'c' => 1,
+            b'G' | b'g' => 2,
+            b'T' | b't' => 3,
+            _ => {
+                valid = 0;
+                continue;
+            }
+        };
+        code = ((code << 2) | bits) & 63;
+        valid += 1;
+        if valid >= 3 {
+            counts[code] += 1;
+            triplets += 1;
+        }
+    }
+
+    if triplets < 2 {
+        return 0.0;
+    }
+    let sum: u64 = counts
+        .iter()
+        .map(|&c| c as u64 * c.saturating_sub(1) as u64 / 2)
+        .sum();
+    sum as f64 / (triplets - 1) as f64
+}
+
+/// Condition a record must satisfy to pass a [`RecordFilter`].
+///
+/// Quality predicates pass records without quality strings.
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    MinLength(usize),
+    MaxLength(usize),
+    /// Maximum fraction of `N` bases.
+    MaxNFraction(f64),
+    MinMeanQuality(f64),
+    MaxExpectedErrors(f64),
+    /// Maximum [`dust_score`].
+    MaxDust(f64),
+    /// Record name must match. Requires the `regex` feature.
+    #[cfg(feature = "regex")]
+    IdRegex(Regex),
+    /// Record name must be in the set.
+    IdList(HashSet<Vec<u8>>),
+    /// Inverts a predicate.
+    Not(Box<Predicate>),
+    /// Passes if any inner predicate passes.
+    Any(Vec<Predicate>),
+}
+
+impl Predicate {
+    /// Returns `true` if `record` passes. `offset` is the quality ASCII offset.
+    pub fn test(&self, record: &Record, offset: u8) -> bool {
+        let has_qual = !record.qual.is_empty();
+        match self {
+            Predicate::MinLength(min) => record.seq.len() >= *min,
+            Predicate::MaxLength(max) => record.seq.len() <= *max,
+            Predicate::MaxNFraction(max) => {
+                let n = record
+                    .seq
+                    .iter()
+                    .filter(|&&b| b == b'N' || b == b'n')
+                    .count();
+                record.seq.is_empty() || n as f64 / record.seq.len() as f64 <= *max
+            }
+            Predicate::MinMeanQuality(min) => {
+                !has_qual || QualityStats::from_qual(&record.qual, offset).mean() >= *min
+            }
+            Predicate::MaxExpectedErrors(max) => {
+                !has_qual || QualityStats::from_qual(&record.qual, offset).expected_errors() <= *max
+            }
+            Predicate::MaxDust(max) => dust_score(&record.seq) <= *max,
+            #[cfg(feature = "regex")]
+            Predicate::IdRegex(regex) => regex.is_match(record.name()),
+            Predicate::IdList(ids) => ids.contains(record.name()),
+            Predicate::Not(inner) => !inner.test(record, offset),
+            Predicate::Any(inner) => inner.iter().any(|p| p.test(record, offset)),
+        }
+    }
+
+    /// Short name reported as the rejection reason.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Predicate::MinLength(_) => "min_length",
+            Predicate::MaxLength(_) => "max_length",
+            Predicate::MaxNFraction(_) => "max_n_fraction",
+            Predicate::MinMeanQuality(_) => "min_mean_quality",
+            Predicate::MaxExpectedErrors(_) => "max_expected_errors",
+            Predicate::MaxDust(_) => "low_complexity",
+            #[cfg(feature = "regex")]
+            Predicate::IdRegex(_) => "id_regex",
+            Predicate::IdList(_) => "id_list",
+            Predicate::Not(_) => "not",
+            Predicate::Any(_) => "any",
+        }
+    }
+}
+
+/// Pass/reject counts from [`RecordFilter::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterSummary {
+    pub passed: u64,
+    pub rejected: u64,
+}
+
+/// Conjunction of predicates applied to each record.
+#[derive(Debug, Clone)]
+pub struct RecordFilter {
+    predicates: Vec<Predicate>,
+    offset: u8,
+}
+
+impl Default for RecordFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordFilter {
+    /// Creates a filter that passes everything, for Phred+33 data.
+    pub fn new() -> Self {
+        Self {
+            predicates: Vec::new(),
+            offset: QualityEncoding::Phred33.offset(),
+        }
+    }
+
+    /// Adds a predicate that every record must pass.
+    pub fn with(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Sets the ASCII offset of quality characters.
+    pub fn with_offset(mut self, offset: u8) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Returns the first predicate `record` fails, or `None` if it passes.
+    pub fn check(&self, record: &Record) -> Option<&Predicate> {
+        self.predicates
+            .iter()
+            .find(|p| !p.test(record, self.offset))
+    }
+
+    /// Filters every remaining record of `reader` into `passed`. Rejected
+    /// records go to `rejected`, if given, with an `XF:Z:<reason>` tag.
+    ///
+    /// Output is FASTQ, so records without a quality string for every base,
+    /// such as FASTA records, are an error.
+    pub fn run<E, W1, W2>(
+        &self,
+        reader: &mut RecordReader<E>,
+        passed: &mut FastqWriter<W1>,
+        mut rejected: Option<&mut FastqWriter<W2>>,
+    ) -> Result<FilterSummary, ReaderError>
+    where
+        E: EventReader,
+        W1: Write,
+        W2: Write,
+    {
+        let mut record = Record::new();
+        let mut summary = FilterSummary::default();
+
+        while reader.read_record(&mut record)? {
+            if record.qual.len() != record.seq.len() {
+                return Err(ReaderError::InvalidFormat {
+                    message: format!(
+                        "Record {} has no quality string to write as FASTQ",
+                        summary.passed + summary.rejected + 1
+                    ),
+                });
+            }
+            match self.check(&record) {
+                None => {
+                    passed.write_record(&record)?;
+                    summary.passed += 1;
+                }
+                Some(predicate) => {
+                    if let Some(writer) = rejected.as_deref_mut() {
+                        let tag = SamTag::string(*b"XF", predicate.reason().as_bytes());
+                        writer.write_record_with_tags(&record, &[tag])?;
+                    }
+                    summary.rejected += 1;
+                }
+            }
+        }
+
+        passed.flush()?;
+        if let Some(writer) = rejected {
+            writer.flush()?;
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FastaReader, FastqReader};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_dust_score() {
+        assert!(dust_score(b"AAAAAAAAAAAAAAAAAAAA") > 8.0);
+        assert!(dust_score(b"ACGTTGCAAGCTTCGAGATC") < 1.0);
+        assert_eq!(dust_score(b"AC"), 0.0);
+    }
+
+    #[test]
+    fn test_predicates() {
+        let r = Record::from_parts(b"read1 x", b"ACGTNN", b"IIII##");
+        assert!(Predicate::MinLength(6).test(&r, 33));
+        assert!(!Predicate::MaxLength(5).test(&r, 33));
+        assert!(!Predicate::MaxNFraction(0.2).test(&r, 33));
+        assert!(Predicate::MinMeanQuality(27.0).test(&r, 33));
+        assert!(!Predicate::MaxExpectedErrors(1.0).test(&r, 33));
+        assert!(Predicate::IdList([b"read1".to_vec()].into()).test(&r, 33));
+        assert!(Predicate::Not(Box::new(Predicate::MaxLength(5))).test(&r, 33));
+        assert!(
+            Predicate::Any(vec![Predicate::MaxLength(5), Predicate::MinLength(1)]).test(&r, 33)
+        );
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_id_regex() {
+        let r = Record::from_parts(b"read1 x", b"ACGT", b"IIII");
+        assert!(Predicate::IdRegex(Regex::new("^read\\d$").unwrap()).test(&r, 33));
+        assert!(!Predicate::IdRegex(Regex::new("x").unwrap()).test(&r, 33));
+    }
+
+    #[test]
+    fn test_run_rejects_fasta() {
+        let data = b">r1\nACGT\n";
+        let mut reader = RecordReader::new(FastaReader::new(Cursor::new(&data[..])));
+        let mut passed = FastqWriter::new(Vec::new());
+        let result =
+            RecordFilter::new().run(&mut reader, &mut passed, None::<&mut FastqWriter<Vec<u8>>>);
+        assert!(matches!(result, Err(ReaderError::InvalidFormat { .. })));
+    }
+
+    #[test]
+    fn test_run_with_rejects() {
+        let data = b"@r1\nACGTACGT\n+\nIIIIIIII\n@r2\nAC\n+\nII\n@r3\nACGTACGT\n+\n########\n";
+        let mut reader = RecordReader::new(FastqReader::new(Cursor::new(&data[..])));
+        let filter = RecordFilter::new()
+            .with(Predicate::MinLength(4))
+            .with(Predicate::MinMeanQuality(20.0));
+        let mut passed = FastqWriter::new(Vec::new());
+        let mut rejected = FastqWriter::new(Vec::new());
+
+        let summary = filter
+            .run(&mut reader, &mut passed, Some(&mut rejected))
+            .unwrap();
+
+        assert_eq!(
+            summary,
+            FilterSummary {
+                passed: 1,
+                rejected: 2
+            }
+        );
+        assert_eq!(
+            &passed.into_inner().unwrap(),
+            b"@r1\nACGTACGT\n+\nIIIIIIII\n"
+        );
+        assert_eq!(
+            &rejected.into_inner().unwrap(),
+            b"@r2\tXF:Z:min_length\nAC\n+\nII\n@r3\tXF:Z:min_mean_quality\nACGTACGT\n+\n########\n"
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::io::{self, Write};
+
+use memchr::memchr2;
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::util::split_name;
+
+/// Run of `N` bases in a record, as a 0-based half-open interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gap {
+    /// Record name: the header up to the first space or tab.
+    pub name: Vec<u8>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Gap {
+    /// Length of the gap.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the gap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Writes the gap as a BED line (`name`, `start`, `end`).
+    pub fn write_bed<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.name)?;
+        writeln!(writer, "\t{}\t{}", self.start, self.end)
+    }
+}
+
+/// Finds runs of `N`/`n` from `SeqChunk` events, across chunk and line
+/// boundaries, for assembly gap reports.
+#[derive(Debug, Clone)]
+pub struct GapFinder {
+    min_len: usize,
+    id: Vec<u8>,
+    pos: usize,
+    run_start: Option<usize>,
+}
+
+impl GapFinder {
+    /// Creates a finder reporting runs of at least `min_len` bases.
+    pub fn new(min_len: usize) -> Self {
+        Self {
+            min_len: min_len.max(1),
+            id: Vec::new(),
+            pos: 0,
+            run_start: None,
+        }
+    }
+
+    /// Processes one event, appending the gaps it completes to `out`.
+    pub fn observe(&mut self, event: &Event, out: &mut Vec<Gap>) {
+        match *event {
+            Event::NextRecord => {
+                self.finish(out);
+                self.id.clear();
+                self.pos = 0;
+            }
+            Event::IdChunk(chunk) => self.id.extend_from_slice(chunk),
+            Event::SeqChunk(chunk) => {
+                let mut i = 0;
+                while i < chunk.len() {
+                    if self.run_start.is_some() {
+                        match chunk[i..].iter().position(|&b| b != b'N' && b != b'n') {
+                            Some(len) => {
+                                i += len;
+                                self.end_run(self.pos + i, out);
+                            }
+                            None => break,
+                        }
+                    } else {
+                        match memchr2(b'N', b'n', &chunk[i..]) {
+                            Some(offset) => {
+                                i += offset;
+                                self.run_start = Some(self.pos + i);
+                            }
+                            None => break,
+                        }
+                    }
+                }
+                self.pos += chunk.len();
+            }
+            Event::QualChunk(_) => {}
+        }
+    }
+
+    /// Completes a gap running to the end of the last record. Call at the end
+    /// of input.
+    pub fn finish(&mut self, out: &mut Vec<Gap>) {
+        self.end_run(self.pos, out);
+    }
+
+    fn end_run(&mut self, end: usize, out: &mut Vec<Gap>) {
+        let Some(start) = self.run_start.take() else {
+            return;
+        };
+        if end - start >= self.min_len {
+            out.push(Gap {
+                name: split_name(&self.id).0.to_vec(),
+                start,
+                end,
+            });
+        }
+    }
+
+    /// Writes every gap of `reader` to `writer` as BED. Returns the number of
+    /// gaps written.
+    pub fn run<E: EventReader, W: Write>(
+        &mut self,
+        reader: &mut E,
+        writer: &mut W,
+    ) -> Result<u64, ReaderError> {
+        let mut gaps = Vec::new();
+        let mut written = 0;
+        let mut done = false;
+
+        while !done {
+            match reader.next_event() {
+                Some(event) => self.observe(&event?, &mut gaps),
+                None => {
+                    self.finish(&mut gaps);
+                    done = true;
+                }
+            }
+            for gap in gaps.drain(..) {
+                gap.write_bed(writer)?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastaReader;
+
+    const DATA: &[u8] = b">chr1 assembled\nACNNN\nNNGTNA\nCGnn\n>chr2\nNNNN\nNNAC\n>chr3\nACGTNN\n";
+
+    fn gaps(capacity: usize, min_len: usize) -> Vec<Gap> {
+        let mut finder = GapFinder::new(min_len);
+        let mut reader = FastaReader::with_capacity(capacity, DATA);
+        let mut gaps = Vec::new();
+        while let Some(event) = reader.next_event() {
+            finder.observe(&event.unwrap(), &mut gaps);
+        }
+        finder.finish(&mut gaps);
+        gaps
+    }
+
+    fn gap(name: &[u8], start: usize, end: usize) -> Gap {
+        Gap {
+            name: name.to_vec(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_gaps_across_lines_and_chunks() {
+        let expected = vec![
+            gap(b"chr1", 2, 7),
+            gap(b"chr1", 9, 10),
+            gap(b"chr1", 13, 15),
+            gap(b"chr2", 0, 6),
+            gap(b"chr3", 4, 6),
+        ];
+        for capacity in [1, 3, 64] {
+            assert_eq!(gaps(capacity, 1), expected);
+        }
+    }
+
+    #[test]
+    fn test_min_length() {
+        assert_eq!(gaps(4, 5), vec![gap(b"chr1", 2, 7), gap(b"chr2", 0, 6)]);
+    }
+
+    #[test]
+    fn test_bed_output() {
+        let mut out = Vec::new();
+        let written = GapFinder::new(3)
+            .run(&mut FastaReader::new(DATA), &mut out)
+            .unwrap();
+        assert_eq!(written, 2);
+        assert_eq!(out, b"chr1\t2\t7\nchr2\t0\t6\n");
+    }
+}
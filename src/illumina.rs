@@ -0,0 +1,174 @@
+use crate::error::ReaderError;
+use crate::event::EventReader;
+use crate::record::{Record, RecordReader};
+use crate::util::{parse_num, split_name};
+
+/// Fields of an Illumina/Casava 1.8+ read header, borrowed from the header.
+///
+/// `instrument:run:flowcell:lane:tile:x:y[:umi] read:filtered:control:index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IlluminaHeader<'a> {
+    pub instrument: &'a [u8],
+    pub run: u32,
+    pub flowcell: &'a [u8],
+    pub lane: u32,
+    pub tile: u32,
+    pub x: u32,
+    pub y: u32,
+    /// Optional eighth name field written by bcl2fastq/bcl-convert.
+    pub umi: Option<&'a [u8]>,
+    /// Casava comment fields, if the header has a comment.
+    pub comment: Option<CasavaComment<'a>>,
+}
+
+/// The `read:filtered:control:index` comment of an Illumina header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CasavaComment<'a> {
+    /// Read number (1 or 2; 3 for some index reads).
+    pub read: u8,
+    /// `true` if the read failed the chip (chastity) filter (`Y`).
+    pub filtered: bool,
+    pub control: u32,
+    /// Index sequence, or sample number on older instruments.
+    /// Dual indexes are joined with `+`.
+    pub index: &'a [u8],
+}
+
+impl<'a> IlluminaHeader<'a> {
+    /// Parses an assembled header (without the leading `@`).
+    /// Returns `None` if the name does not follow the Illumina layout.
+    pub fn parse(id: &'a [u8]) -> Option<Self> {
+        let (name, rest) = split_name(id);
+
+        let mut fields = name.split(|&b| b == b':');
+        let instrument = fields.next()?;
+        let run = parse_num(fields.next()?)?;
+        let flowcell = fields.next()?;
+        let lane = parse_num(fields.next()?)?;
+        let tile = parse_num(fields.next()?)?;
+        let x = parse_num(fields.next()?)?;
+        let y = parse_num(fields.next()?)?;
+        let umi = fields.next();
+        if fields.next().is_some() {
+            return None;
+        }
+
+        let comment = match rest {
+            Some(rest) => Some(CasavaComment::parse(rest)?),
+            None => None,
+        };
+
+        Some(Self {
+            instrument,
+            run,
+            flowcell,
+            lane,
+            tile,
+            x,
+            y,
+            umi,
+            comment,
+        })
+    }
+
+    /// Returns `true` if the read is flagged `Y` by the chip filter.
+    pub fn is_filtered(&self) -> bool {
+        self.comment.is_some_and(|c| c.filtered)
+    }
+}
+
+impl<'a> CasavaComment<'a> {
+    /// Parses the first token of a header comment.
+    pub fn parse(comment: &'a [u8]) -> Option<Self> {
+        let (token, _) = split_name(comment);
+
+        let mut fields = token.splitn(4, |&b| b == b':');
+        let read = parse_num(fields.next()?)?;
+        let filtered = match fields.next()? {
+            b"Y" => true,
+            b"N" => false,
+            _ => return None,
+        };
+        let control = parse_num(fields.next()?)?;
+        let index = fields.next()?;
+
+        Some(Self {
+            read,
+            filtered,
+            control,
+            index,
+        })
+    }
+}
+
+/// Returns `false` only for Illumina headers flagged `Y` by the chip filter.
+pub fn passes_chastity_filter(id: &[u8]) -> bool {
+    !IlluminaHeader::parse(id).is_some_and(|h| h.is_filtered())
+}
+
+/// Reads the next record that passes the chip filter, skipping `Y` reads.
+/// Returns `false` at EOF.
+pub fn read_passing_record<E: EventReader>(
+    reader: &mut RecordReader<E>,
+    record: &mut Record,
+) -> Result<bool, ReaderError> {
+    while reader.read_record(record)? {
+        if passes_chastity_filter(&record.id) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastqReader;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_full_header() {
+        let id = b"A00123:45:HXXXXDSXY:2:1101:12345:1000 1:N:0:ACGTACGT+TTGGCCAA";
+        let header = IlluminaHeader::parse(id).unwrap();
+
+        assert_eq!(header.instrument, b"A00123");
+        assert_eq!(header.run, 45);
+        assert_eq!(header.flowcell, b"HXXXXDSXY");
+        assert_eq!(header.lane, 2);
+        assert_eq!(header.tile, 1101);
+        assert_eq!((header.x, header.y), (12345, 1000));
+        assert_eq!(header.umi, None);
+
+        let comment = header.comment.unwrap();
+        assert_eq!(comment.read, 1);
+        assert!(!comment.filtered);
+        assert_eq!(comment.control, 0);
+        assert_eq!(comment.index, b"ACGTACGT+TTGGCCAA");
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        let header = IlluminaHeader::parse(b"M1:1:FC:1:2:3:4:ACGTAC").unwrap();
+        assert_eq!(header.umi, Some(&b"ACGTAC"[..]));
+        assert_eq!(header.comment, None);
+
+        assert!(IlluminaHeader::parse(b"SRR000001.1").is_none());
+        assert!(IlluminaHeader::parse(b"M1:1:FC:1:2:3:4 1:X:0:ACGT").is_none());
+    }
+
+    #[test]
+    fn test_chastity_filter() {
+        let data = b"@M1:1:FC:1:1:1:1 1:N:0:1\nACGT\n+\nIIII\n\
+                     @M1:1:FC:1:1:1:2 1:Y:0:1\nACGT\n+\nIIII\n\
+                     @other\nACGT\n+\nIIII\n";
+        let mut reader = RecordReader::new(FastqReader::new(Cursor::new(&data[..])));
+        let mut record = Record::new();
+        let mut names = Vec::new();
+
+        while read_passing_record(&mut reader, &mut record).unwrap() {
+            names.push(record.name().to_vec());
+        }
+
+        assert_eq!(names, vec![b"M1:1:FC:1:1:1:1".to_vec(), b"other".to_vec()]);
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+
+/// 2-bit code of a base (`A=0, C=1, G=2, T=3`), case-insensitive.
+pub fn encode_base(base: u8) -> Option<u64> {
+    match base {
+        b'A' | b'a' => Some(0),
+        b'C' | b'c' => Some(1),
+        b'G' | b'g' => Some(2),
+        b'T' | b't' => Some(3),
+        _ => None,
+    }
+}
+
+/// Decodes a 2-bit encoded k-mer back to uppercase bases.
+pub fn decode_kmer(kmer: u64, k: usize) -> Vec<u8> {
+    (0..k)
+        .rev()
+        .map(|i| b"ACGT"[((kmer >> (2 * i)) & 3) as usize])
+        .collect()
+}
+
+/// 64-bit mix of a k-mer with a seed (splitmix64 finalizer).
+pub fn hash_kmer(kmer: u64, seed: u64) -> u64 {
+    let mut x = kmer ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Forward and reverse-complement k-mer over a stream of bases.
+///
+/// Bases are pushed one at a time, so the k-mer carries over chunk and line
+/// boundaries. Non-ACGT bases restart it.
+#[derive(Debug, Clone)]
+pub struct RollingKmer {
+    k: usize,
+    mask: u64,
+    forward: u64,
+    reverse: u64,
+    valid: usize,
+}
+
+impl RollingKmer {
+    /// Creates an empty rolling k-mer.
+    ///
+    /// Panics if `k` is 0 or greater than 32.
+    pub fn new(k: usize) -> Self {
+        assert!((1..=32).contains(&k), "k must be between 1 and 32, got {k}");
+        Self {
+            k,
+            mask: if k == 32 {
+                u64::MAX
+            } else {
+                (1 << (2 * k)) - 1
+            },
+            forward: 0,
+            reverse: 0,
+            valid: 0,
+        }
+    }
+
+    /// The k-mer length.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Adds a base. Returns `true` if the last `k` bases form a k-mer.
+    #[inline]
+    pub fn push(&mut self, base: u8) -> bool {
+        let Some(code) = encode_base(base) else {
+            self.valid = 0;
+            return false;
+        };
+        self.forward = ((self.forward << 2) | code) & self.mask;
+        self.reverse = (self.reverse >> 2) | ((3 - code) << (2 * (self.k - 1)));
+        self.valid += 1;
+        self.valid >= self.k
+    }
+
+    /// Forgets all bases, e.g. at a record boundary.
+    pub fn reset(&mut self) {
+        self.valid = 0;
+    }
+
+    /// The current k-mer as read.
+    pub fn forward(&self) -> u64 {
+        self.forward
+    }
+
+    /// Reverse complement of the current k-mer.
+    pub fn reverse(&self) -> u64 {
+        self.reverse
+    }
+
+    /// The smaller of the forward and reverse-complement k-mers.
+    pub fn canonical(&self) -> u64 {
+        self.forward.min(self.reverse)
+    }
+}
+
+/// Storage backend of a [`KmerCounter`].
+pub trait KmerCounts {
+    fn increment(&mut self, kmer: u64);
+    fn count(&self, kmer: u64) -> u64;
+}
+
+/// Exact counts.
+impl KmerCounts for HashMap<u64, u64> {
+    fn increment(&mut self, kmer: u64) {
+        *self.entry(kmer).or_insert(0) += 1;
+    }
+
+    fn count(&self, kmer: u64) -> u64 {
+        self.get(&kmer).copied().unwrap_or(0)
+    }
+}
+
+/// Count-min sketch: fixed memory, counts may be overestimated but never
+/// underestimated.
+#[derive(Debug, Clone)]
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    table: Vec<u32>,
+}
+
+impl CountMinSketch {
+    /// Creates a sketch of `depth` rows of `width` counters.
+    ///
+    /// Panics if `width` or `depth` is 0.
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0, "count-min sketch must not be empty");
+        Self {
+            width,
+            depth,
+            table: vec![0; width * depth],
+        }
+    }
+
+    fn slot(&self, row: usize, kmer: u64) -> usize {
+        row * self.width + (hash_kmer(kmer, row as u64) % self.width as u64) as usize
+    }
+}
+
+impl KmerCounts for CountMinSketch {
+    fn increment(&mut self, kmer: u64) {
+        for row in 0..self.depth {
+            let slot = self.slot(row, kmer);
+            self.table[slot] = self.table[slot].saturating_add(1);
+        }
+    }
+
+    fn count(&self, kmer: u64) -> u64 {
+        (0..self.depth)
+            .map(|row| self.table[self.slot(row, kmer)] as u64)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+/// Counts k-mers directly from `SeqChunk` events.
+///
+/// K-mers span chunk and line boundaries but not records, and never contain
+/// non-ACGT bases. Counting is canonical by default.
+#[derive(Debug, Clone)]
+pub struct KmerCounter<C: KmerCounts = HashMap<u64, u64>> {
+    kmer: RollingKmer,
+    canonical: bool,
+    counts: C,
+    total: u64,
+}
+
+impl KmerCounter {
+    /// Creates an exact counter for `k`-mers.
+    ///
+    /// Panics if `k` is 0 or greater than 32.
+    pub fn new(k: usize) -> Self {
+        Self::with_counts(k, HashMap::new())
+    }
+}
+
+impl<C: KmerCounts> KmerCounter<C> {
+    /// Creates a counter for `k`-mers storing into `counts`.
+    ///
+    /// Panics if `k` is 0 or greater than 32.
+    pub fn with_counts(k: usize, counts: C) -> Self {
+        Self {
+            kmer: RollingKmer::new(k),
+            canonical: true,
+            counts,
+            total: 0,
+        }
+    }
+
+    /// Sets whether a k-mer and its reverse complement are counted together.
+    pub fn with_canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// Adds one event.
+    pub fn observe(&mut self, event: &Event) {
+        match *event {
+            Event::NextRecord => self.kmer.reset(),
+            Event::SeqChunk(chunk) => {
+                for &b in chunk {
+                    if self.kmer.push(b) {
+                        let kmer = if self.canonical {
+                            self.kmer.canonical()
+                        } else {
+                            self.kmer.forward()
+                        };
+                        self.counts.increment(kmer);
+                        self.total += 1;
+                    }
+                }
+            }
+            Event::IdChunk(_) | Event::QualChunk(_) => {}
+        }
+    }
+
+    /// Consumes all remaining events of `reader`.
+    pub fn observe_all<E: EventReader>(&mut self, reader: &mut E) -> Result<(), ReaderError> {
+        while let Some(event) = reader.next_event() {
+            self.observe(&event?);
+        }
+        Ok(())
+    }
+
+    /// Count of `kmer`, encoded as by [`RollingKmer`].
+    pub fn count(&self, kmer: u64) -> u64 {
+        self.counts.count(kmer)
+    }
+
+    /// Count of the k-mer spelled by `bases`, or 0 if it is not a valid k-mer.
+    pub fn count_bases(&self, bases: &[u8]) -> u64 {
+        if bases.len() != self.kmer.k() {
+            return 0;
+        }
+        let mut kmer = RollingKmer::new(self.kmer.k());
+        if bases.iter().map(|&b| kmer.push(b)).last() != Some(true) {
+            return 0;
+        }
+        if self.canonical {
+            self.count(kmer.canonical())
+        } else {
+            self.count(kmer.forward())
+        }
+    }
+
+    /// Number of k-mers counted.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The counts backend.
+    pub fn counts(&self) -> &C {
+        &self.counts
+    }
+
+    /// Returns the counts backend.
+    pub fn into_counts(self) -> C {
+        self.counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastaReader;
+    use std::io::Cursor;
+
+    fn naive_counts(seqs: &[&[u8]], k: usize) -> HashMap<u64, u64> {
+        let mut counts = HashMap::new();
+        for seq in seqs {
+            for window in seq.windows(k) {
+                if let Some(fwd) = window
+                    .iter()
+                    .try_fold(0u64, |acc, &b| Some((acc << 2) | encode_base(b)?))
+                {
+                    let rev = window
+                        .iter()
+                        .rev()
+                        .fold(0u64, |acc, &b| (acc << 2) | (3 - encode_base(b).unwrap()));
+                    counts.increment(fwd.min(rev));
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_rolling_kmer() {
+        let mut kmer = RollingKmer::new(3);
+        assert!(!kmer.push(b'A'));
+        assert!(!kmer.push(b'c'));
+        assert!(kmer.push(b'G'));
+        assert_eq!(decode_kmer(kmer.forward(), 3), b"ACG");
+        assert_eq!(decode_kmer(kmer.reverse(), 3), b"CGT");
+        assert!(!kmer.push(b'N'));
+        assert!(!kmer.push(b'T'));
+
+        let mut full = RollingKmer::new(32);
+        let seq = b"ACGTACGTACGTACGTACGTACGTACGTACGTT";
+        let ready: Vec<_> = seq.iter().map(|&b| full.push(b)).collect();
+        assert_eq!(ready.iter().filter(|&&r| r).count(), 2);
+        assert_eq!(decode_kmer(full.forward(), 32), &seq[1..]);
+    }
+
+    #[test]
+    fn test_counts_across_chunks_and_records() {
+        let data = b">a\nACGTAC\nGTNNACG\nT\n>b\nTTTTG\n";
+        let mut counter = KmerCounter::new(4);
+        counter
+            .observe_all(&mut FastaReader::with_capacity(3, Cursor::new(&data[..])))
+            .unwrap();
+
+        let expected = naive_counts(&[b"ACGTACGTNNACGT", b"TTTTG"], 4);
+        assert_eq!(counter.counts(), &expected);
+        assert_eq!(counter.total(), 8);
+        assert_eq!(counter.count_bases(b"CAAA"), 1);
+        assert_eq!(counter.count_bases(b"ACGT"), 3);
+        assert_eq!(counter.count_bases(b"ACNT"), 0);
+    }
+
+    #[test]
+    fn test_count_min_sketch() {
+        let data = b">a\nACGTTGCAAGCTTCGAGATCACGTTGCA\n";
+        let mut exact = KmerCounter::new(5).with_canonical(false);
+        let mut sketch =
+            KmerCounter::with_counts(5, CountMinSketch::new(1 << 12, 4)).with_canonical(false);
+        exact
+            .observe_all(&mut FastaReader::new(Cursor::new(&data[..])))
+            .unwrap();
+        sketch
+            .observe_all(&mut FastaReader::new(Cursor::new(&data[..])))
+            .unwrap();
+
+        for (&kmer, &count) in exact.counts() {
+            assert!(sketch.count(kmer) >= count);
+        }
+        assert_eq!(sketch.total(), exact.total());
+    }
+}
@@ -1,11 +1,67 @@
 #![doc = include_str!("../README.md")]
 
+mod adapter;
+mod alphabet;
+mod demux;
 mod error;
 mod event;
+mod extract;
 mod fasta;
 mod fastq;
+mod filter;
+mod gap;
+mod illumina;
+mod kmer;
+mod long_read;
+mod minimizer;
+mod packed;
+mod paired;
+mod qc;
+mod quality;
+mod record;
+mod revcomp;
+mod sam_tag;
+mod sketch;
+mod stats;
+mod trim;
+mod twobit;
+mod umi;
+mod util;
+mod writer;
 
+pub use adapter::{Adapter, AdapterDetector, AdapterTrimmer, KNOWN_ADAPTERS, detect_adapter};
+pub use alphabet::Alphabet;
+pub use demux::{DemuxSummary, Demultiplexer, IndexSource, Sample, SampleSheet, UNDETERMINED};
 pub use error::ReaderError;
-pub use event::Event;
+pub use event::{Event, EventReader};
+pub use extract::{ExtractSummary, Interval, IntervalExtractor, parse_bed, parse_gff};
 pub use fasta::FastaReader;
 pub use fastq::FastqReader;
+pub use filter::{FilterSummary, Predicate, RecordFilter, dust_score};
+pub use gap::{Gap, GapFinder};
+pub use illumina::{CasavaComment, IlluminaHeader, passes_chastity_filter, read_passing_record};
+pub use kmer::{
+    CountMinSketch, KmerCounter, KmerCounts, RollingKmer, decode_kmer, encode_base, hash_kmer,
+};
+pub use long_read::{KeyValues, NanoporeHeader, PacBioName, PacBioRead, key_values};
+pub use minimizer::{MinimizerScanner, Seed, SeedScanner, Seeds, SyncmerScanner};
+pub use packed::{PackedRecord, PackedRecords, PackedSequence};
+pub use paired::{
+    InterleavedFastqReader, PairedFastqReader, deinterleave, interleave, mate_name,
+};
+pub use qc::{BaseCounts, Overrepresented, PositionQuality, Qc, QcReport};
+pub use quality::{
+    QualityDetector, QualityEncoding, QualityStats, decode_phred, detect_quality_encoding,
+    error_probability,
+};
+pub use record::{Record, RecordReader};
+pub use revcomp::{
+    ReverseComplementReader, complement, reverse_complement, reverse_complement_in_place,
+};
+pub use sam_tag::{SamTag, SamTagValue, SamTags, sam_tags};
+pub use sketch::{RecordSketches, Sketch, SketchMode, Sketcher};
+pub use stats::{Stats, StatsSummary};
+pub use trim::{QualityTrimmer, TrimmingReader};
+pub use twobit::{TwoBitReader, TwoBitWriter};
+pub use umi::{BarcodeBase, BarcodePattern, UmiExtractor};
+pub use writer::{FastaWriter, FastqWriter};
@@ -0,0 +1,192 @@
+use memchr::memchr;
+
+use crate::util::{parse_num, split_name};
+
+/// Iterator over whitespace-separated `key=value` pairs in a header comment.
+/// Tokens without `=` are skipped.
+#[derive(Debug, Clone)]
+pub struct KeyValues<'a> {
+    rest: &'a [u8],
+}
+
+/// Returns an iterator over the `key=value` pairs of `description`.
+pub fn key_values(description: &[u8]) -> KeyValues<'_> {
+    KeyValues { rest: description }
+}
+
+impl<'a> Iterator for KeyValues<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.rest.iter().position(|b| !b.is_ascii_whitespace())?;
+            let rest = &self.rest[start..];
+            let end = rest
+                .iter()
+                .position(|b| b.is_ascii_whitespace())
+                .unwrap_or(rest.len());
+            let token = &rest[..end];
+            self.rest = &rest[end..];
+
+            if let Some(eq) = memchr(b'=', token) {
+                return Some((&token[..eq], &token[eq + 1..]));
+            }
+        }
+    }
+}
+
+/// Oxford Nanopore read header, e.g.
+/// `<uuid> runid=... read=12 ch=301 start_time=... flow_cell_id=...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NanoporeHeader<'a> {
+    /// Read UUID (the header name).
+    pub read_id: &'a [u8],
+    pub run_id: Option<&'a [u8]>,
+    /// Read number within the channel.
+    pub read_number: Option<u64>,
+    pub channel: Option<u32>,
+    /// ISO 8601 acquisition start time, unparsed.
+    pub start_time: Option<&'a [u8]>,
+    pub flow_cell_id: Option<&'a [u8]>,
+    /// Raw `key=value` text for tags not covered above.
+    pub description: &'a [u8],
+}
+
+impl<'a> NanoporeHeader<'a> {
+    /// Parses an assembled header. Returns `None` unless it carries at least
+    /// a `runid` or `ch` tag.
+    pub fn parse(id: &'a [u8]) -> Option<Self> {
+        let (read_id, description) = split_name(id);
+        let description = description.unwrap_or_default();
+        let mut header = Self {
+            read_id,
+            run_id: None,
+            read_number: None,
+            channel: None,
+            start_time: None,
+            flow_cell_id: None,
+            description,
+        };
+
+        for (key, value) in key_values(description) {
+            match key {
+                b"runid" => header.run_id = Some(value),
+                b"read" => header.read_number = parse_num(value),
+                b"ch" => header.channel = parse_num(value),
+                b"start_time" => header.start_time = Some(value),
+                b"flow_cell_id" => header.flow_cell_id = Some(value),
+                _ => {}
+            }
+        }
+
+        (header.run_id.is_some() || header.channel.is_some()).then_some(header)
+    }
+
+    /// Iterates over all `key=value` tags.
+    pub fn tags(&self) -> KeyValues<'a> {
+        key_values(self.description)
+    }
+}
+
+/// Kind of PacBio read encoded in the name suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacBioRead {
+    /// `movie/zmw/start_end` subread with query coordinates.
+    Subread { start: u64, end: u64 },
+    /// `movie/zmw/ccs` consensus read (including `ccs/fwd`, `ccs/rev`).
+    Ccs,
+    /// `movie/zmw` with no suffix.
+    Zmw,
+}
+
+/// PacBio read name, `movie/zmw/start_end` or `movie/zmw/ccs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacBioName<'a> {
+    pub movie: &'a [u8],
+    pub zmw: u64,
+    pub read: PacBioRead,
+}
+
+impl<'a> PacBioName<'a> {
+    /// Parses the name part of an assembled header.
+    pub fn parse(id: &'a [u8]) -> Option<Self> {
+        let (name, _) = split_name(id);
+        let mut parts = name.splitn(3, |&b| b == b'/');
+        let movie = parts.next().filter(|m| !m.is_empty())?;
+        let zmw = parse_num(parts.next()?)?;
+
+        let read = match parts.next() {
+            None => PacBioRead::Zmw,
+            Some(b"ccs") => PacBioRead::Ccs,
+            Some(suffix) if suffix.starts_with(b"ccs/") => PacBioRead::Ccs,
+            Some(suffix) => {
+                let sep = memchr(b'_', suffix)?;
+                PacBioRead::Subread {
+                    start: parse_num(&suffix[..sep])?,
+                    end: parse_num(&suffix[sep + 1..])?,
+                }
+            }
+        };
+
+        Some(Self { movie, zmw, read })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_values() {
+        let pairs: Vec<_> = key_values(b"  a=1 flag b=x=y\tc=").collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (&b"a"[..], &b"1"[..]),
+                (&b"b"[..], &b"x=y"[..]),
+                (&b"c"[..], &b""[..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nanopore_header() {
+        let id = b"0a1b2c3d-uuid runid=abc123 sampleid=s1 read=42 ch=301 \
+                   start_time=2023-01-01T00:00:00Z flow_cell_id=FAQ12345";
+        let header = NanoporeHeader::parse(id).unwrap();
+
+        assert_eq!(header.read_id, b"0a1b2c3d-uuid");
+        assert_eq!(header.run_id, Some(&b"abc123"[..]));
+        assert_eq!(header.read_number, Some(42));
+        assert_eq!(header.channel, Some(301));
+        assert_eq!(header.start_time, Some(&b"2023-01-01T00:00:00Z"[..]));
+        assert_eq!(header.flow_cell_id, Some(&b"FAQ12345"[..]));
+        assert!(header.tags().any(|(k, v)| k == b"sampleid" && v == b"s1"));
+
+        assert!(NanoporeHeader::parse(b"read1 length=50").is_none());
+    }
+
+    #[test]
+    fn test_pacbio_name() {
+        let name = PacBioName::parse(b"m64011_190830_220126/12/100_2500 RQ=0.9").unwrap();
+        assert_eq!(name.movie, b"m64011_190830_220126");
+        assert_eq!(name.zmw, 12);
+        assert_eq!(
+            name.read,
+            PacBioRead::Subread {
+                start: 100,
+                end: 2500
+            }
+        );
+
+        let name = PacBioName::parse(b"m64011/7/ccs/fwd").unwrap();
+        assert_eq!(name.read, PacBioRead::Ccs);
+
+        assert_eq!(
+            PacBioName::parse(b"m64011/7").unwrap().read,
+            PacBioRead::Zmw
+        );
+        assert!(PacBioName::parse(b"read1").is_none());
+        assert!(PacBioName::parse(b"m1/7/abc").is_none());
+    }
+}
@@ -0,0 +1,402 @@
+use std::collections::VecDeque;
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::kmer::{RollingKmer, encode_base, hash_kmer};
+
+/// A selected k-mer: a minimizer or a syncmer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seed {
+    /// Index of the record, from 0.
+    pub record: usize,
+    /// Start of the k-mer within the record.
+    pub position: usize,
+    /// 2-bit encoded k-mer, canonical if the scanner is.
+    pub kmer: u64,
+    /// [`hash_kmer`] of `kmer` with seed 0, which orders k-mers.
+    pub hash: u64,
+    /// `true` if `kmer` is the reverse complement of the sequence.
+    pub reverse: bool,
+}
+
+/// Selects seeds from `SeqChunk` events.
+pub trait SeedScanner {
+    /// Processes one event, appending the seeds it completes to `out`.
+    fn observe(&mut self, event: &Event, out: &mut Vec<Seed>);
+}
+
+/// Position and k-mer bookkeeping shared by the scanners.
+#[derive(Debug, Clone)]
+struct Cursor {
+    kmer: RollingKmer,
+    canonical: bool,
+    record: usize,
+    position: usize,
+}
+
+impl Cursor {
+    fn new(k: usize) -> Self {
+        Self {
+            kmer: RollingKmer::new(k),
+            canonical: true,
+            record: 0,
+            position: 0,
+        }
+    }
+
+    fn next_record(&mut self) {
+        self.kmer.reset();
+        self.record += 1;
+        self.position = 0;
+    }
+
+    /// Pushes a base, returning the seed of the k-mer it completes.
+    fn push(&mut self, base: u8) -> Option<Seed> {
+        self.position += 1;
+        if !self.kmer.push(base) {
+            return None;
+        }
+        let reverse = self.canonical && self.kmer.reverse() < self.kmer.forward();
+        let kmer = if reverse {
+            self.kmer.reverse()
+        } else {
+            self.kmer.forward()
+        };
+        Some(Seed {
+            record: self.record,
+            position: self.position - self.kmer.k(),
+            kmer,
+            hash: hash_kmer(kmer, 0),
+            reverse,
+        })
+    }
+}
+
+/// Streaming `(w, k)` minimizers: the k-mer with the smallest hash in each
+/// window of `w` consecutive k-mers, leftmost on ties, reported once.
+///
+/// Results match a whole-sequence computation regardless of chunking.
+/// Windows do not span non-ACGT bases or records.
+#[derive(Debug, Clone)]
+pub struct MinimizerScanner {
+    cursor: Cursor,
+    w: usize,
+    window: VecDeque<(Seed, usize)>,
+    run: usize,
+    last: Option<usize>,
+}
+
+impl MinimizerScanner {
+    /// Creates a canonical scanner.
+    ///
+    /// Panics if `w` is 0, or `k` is 0 or greater than 32.
+    pub fn new(w: usize, k: usize) -> Self {
+        assert!(w > 0, "minimizer window must not be empty");
+        Self {
+            cursor: Cursor::new(k),
+            w,
+            window: VecDeque::with_capacity(w),
+            run: 0,
+            last: None,
+        }
+    }
+
+    /// Sets whether k-mers are canonicalised before hashing.
+    pub fn with_canonical(mut self, canonical: bool) -> Self {
+        self.cursor.canonical = canonical;
+        self
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.run = 0;
+        self.last = None;
+    }
+}
+
+impl SeedScanner for MinimizerScanner {
+    fn observe(&mut self, event: &Event, out: &mut Vec<Seed>) {
+        match *event {
+            Event::NextRecord => {
+                self.cursor.next_record();
+                self.reset();
+            }
+            Event::SeqChunk(chunk) => {
+                for &b in chunk {
+                    let Some(seed) = self.cursor.push(b) else {
+                        if encode_base(b).is_none() {
+                            self.reset();
+                        }
+                        continue;
+                    };
+                    let index = self.run;
+                    self.run += 1;
+
+                    while self.window.back().is_some_and(|(s, _)| s.hash > seed.hash) {
+                        self.window.pop_back();
+                    }
+                    self.window.push_back((seed, index));
+                    while self
+                        .window
+                        .front()
+                        .is_some_and(|&(_, i)| i + self.w <= index)
+                    {
+                        self.window.pop_front();
+                    }
+
+                    if self.run >= self.w {
+                        let (min, _) = self.window[0];
+                        if self.last != Some(min.position) {
+                            self.last = Some(min.position);
+                            out.push(min);
+                        }
+                    }
+                }
+            }
+            Event::IdChunk(_) | Event::QualChunk(_) => {}
+        }
+    }
+}
+
+/// Streaming closed syncmers: k-mers whose smallest `s`-mer, by hash, is
+/// the first or last one.
+///
+/// Results match a whole-sequence computation regardless of chunking.
+#[derive(Debug, Clone)]
+pub struct SyncmerScanner {
+    cursor: Cursor,
+    smer: RollingKmer,
+    smers: VecDeque<u64>,
+}
+
+impl SyncmerScanner {
+    /// Creates a canonical scanner.
+    ///
+    /// Panics unless `0 < s <= k <= 32`.
+    pub fn new(k: usize, s: usize) -> Self {
+        assert!(s <= k, "syncmer s ({s}) must not exceed k ({k})");
+        Self {
+            cursor: Cursor::new(k),
+            smer: RollingKmer::new(s),
+            smers: VecDeque::with_capacity(k - s + 1),
+        }
+    }
+
+    /// Sets whether k-mers and s-mers are canonicalised before hashing.
+    pub fn with_canonical(mut self, canonical: bool) -> Self {
+        self.cursor.canonical = canonical;
+        self
+    }
+}
+
+impl SeedScanner for SyncmerScanner {
+    fn observe(&mut self, event: &Event, out: &mut Vec<Seed>) {
+        match *event {
+            Event::NextRecord => {
+                self.cursor.next_record();
+                self.smer.reset();
+                self.smers.clear();
+            }
+            Event::SeqChunk(chunk) => {
+                let span = self.cursor.kmer.k() - self.smer.k() + 1;
+                for &b in chunk {
+                    if self.smer.push(b) {
+                        let smer = if self.cursor.canonical {
+                            self.smer.canonical()
+                        } else {
+                            self.smer.forward()
+                        };
+                        if self.smers.len() == span {
+                            self.smers.pop_front();
+                        }
+                        self.smers.push_back(hash_kmer(smer, 0));
+                    } else {
+                        self.smers.clear();
+                    }
+
+                    if let Some(seed) = self.cursor.push(b) {
+                        let min = self.smers.iter().min().copied();
+                        if min == self.smers.front().copied() || min == self.smers.back().copied() {
+                            out.push(seed);
+                        }
+                    }
+                }
+            }
+            Event::IdChunk(_) | Event::QualChunk(_) => {}
+        }
+    }
+}
+
+/// Iterator over the seeds of an event stream.
+pub struct Seeds<E, S> {
+    reader: E,
+    scanner: S,
+    pending: Vec<Seed>,
+    next: usize,
+}
+
+impl<E: EventReader, S: SeedScanner> Seeds<E, S> {
+    /// Selects seeds from `reader` with `scanner`.
+    pub fn new(reader: E, scanner: S) -> Self {
+        Self {
+            reader,
+            scanner,
+            pending: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Returns the reader and scanner.
+    pub fn into_inner(self) -> (E, S) {
+        (self.reader, self.scanner)
+    }
+}
+
+impl<E: EventReader, S: SeedScanner> Iterator for Seeds<E, S> {
+    type Item = Result<Seed, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(&seed) = self.pending.get(self.next) {
+                self.next += 1;
+                return Some(Ok(seed));
+            }
+            self.pending.clear();
+            self.next = 0;
+
+            match self.reader.next_event()? {
+                Ok(event) => self.scanner.observe(&event, &mut self.pending),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastaReader;
+
+    const DATA: &[u8] = b">a\nACGTTGCAAGCTTCGAGATCACGTTGCAAGG\nTTCAGNACGGTACCATTGACCAGT\n\
+                          >b\nGGGTACCTTAGGACAT\n>c\nACG\n";
+
+    fn seqs() -> Vec<Vec<u8>> {
+        vec![
+            b"ACGTTGCAAGCTTCGAGATCACGTTGCAAGGTTCAGNACGGTACCATTGACCAGT".to_vec(),
+            b"GGGTACCTTAGGACAT".to_vec(),
+            b"ACG".to_vec(),
+        ]
+    }
+
+    fn hash_of(seq: &[u8], canonical: bool) -> Option<(u64, bool)> {
+        let fwd = seq
+            .iter()
+            .try_fold(0u64, |acc, &b| Some((acc << 2) | encode_base(b)?))?;
+        let rev = seq
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &b| (acc << 2) | (3 - encode_base(b).unwrap()));
+        let reverse = canonical && rev < fwd;
+        Some((if reverse { rev } else { fwd }, reverse))
+    }
+
+    fn seed(record: usize, position: usize, seq: &[u8], canonical: bool) -> Option<Seed> {
+        let (kmer, reverse) = hash_of(seq, canonical)?;
+        Some(Seed {
+            record,
+            position,
+            kmer,
+            hash: hash_kmer(kmer, 0),
+            reverse,
+        })
+    }
+
+    fn naive_minimizers(w: usize, k: usize) -> Vec<Seed> {
+        let mut out: Vec<Seed> = Vec::new();
+        for (record, seq) in seqs().iter().enumerate() {
+            let kmers: Vec<_> = (0..=seq.len().saturating_sub(k))
+                .filter(|_| seq.len() >= k)
+                .map(|i| seed(record, i, &seq[i..i + k], true))
+                .collect();
+            for window in kmers.windows(w) {
+                if window.iter().any(Option::is_none) {
+                    continue;
+                }
+                let min = window
+                    .iter()
+                    .flatten()
+                    .reduce(|a, b| if b.hash < a.hash { b } else { a })
+                    .unwrap();
+                if out.last() != Some(min) {
+                    out.push(*min);
+                }
+            }
+        }
+        out
+    }
+
+    fn naive_syncmers(k: usize, s: usize, canonical: bool) -> Vec<Seed> {
+        let mut out = Vec::new();
+        for (record, seq) in seqs().iter().enumerate() {
+            for i in 0..=seq.len().saturating_sub(k) {
+                let Some(kmer) = seq
+                    .get(i..i + k)
+                    .and_then(|m| seed(record, i, m, canonical))
+                else {
+                    continue;
+                };
+                let hashes: Vec<u64> = (0..=k - s)
+                    .map(|j| hash_kmer(hash_of(&seq[i + j..i + j + s], canonical).unwrap().0, 0))
+                    .collect();
+                let min = *hashes.iter().min().unwrap();
+                if hashes[0] == min || hashes[k - s] == min {
+                    out.push(kmer);
+                }
+            }
+        }
+        out
+    }
+
+    fn stream<S: SeedScanner>(capacity: usize, scanner: S) -> Vec<Seed> {
+        Seeds::new(FastaReader::with_capacity(capacity, DATA), scanner)
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_minimizers_match_whole_sequence() {
+        for (w, k) in [(1, 3), (4, 5), (10, 7)] {
+            let expected = naive_minimizers(w, k);
+            assert!(!expected.is_empty());
+            for capacity in [1, 3, 7, 64] {
+                assert_eq!(stream(capacity, MinimizerScanner::new(w, k)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_syncmers_match_whole_sequence() {
+        for (k, s, canonical) in [(7, 3, true), (9, 4, false), (5, 5, true)] {
+            let expected = naive_syncmers(k, s, canonical);
+            assert!(!expected.is_empty());
+            for capacity in [1, 5, 64] {
+                let scanner = SyncmerScanner::new(k, s).with_canonical(canonical);
+                assert_eq!(stream(capacity, scanner), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed_positions() {
+        let seeds = stream(64, MinimizerScanner::new(1, 4).with_canonical(false));
+        assert_eq!(seeds[0].position, 0);
+        assert_eq!(seeds[0].kmer, hash_of(b"ACGT", false).unwrap().0);
+        // The N at offset 36 of record 0 removes the k-mers covering it.
+        assert!(
+            seeds
+                .iter()
+                .all(|s| s.record != 0 || !(33..=36).contains(&s.position))
+        );
+        assert!(seeds.iter().all(|s| s.record != 2));
+    }
+}
@@ -0,0 +1,337 @@
+use std::ops::Range;
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+
+const BASES: &[u8; 4] = b"TCAG";
+
+/// Maps a packed byte to the same four bases in `A=0, C=1, G=2, T=3` order.
+const TO_ACGT: [u8; 256] = {
+    // TCAG code -> ACGT code.
+    const CODES: [u8; 4] = [3, 1, 0, 2];
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut converted = 0;
+        let mut shift = 0;
+        while shift < 8 {
+            converted |= CODES[(byte >> shift) & 3] << shift;
+            shift += 2;
+        }
+        table[byte] = converted;
+        byte += 1;
+    }
+    table
+};
+
+/// Nucleotide sequence packed 4 bases per byte, most significant bits first,
+/// in UCSC .2bit order (`T=0, C=1, A=2, G=3`).
+///
+/// Bases other than ACGT are stored as runs of `N` and lowercase bases as
+/// mask runs, so decoding restores the input up to IUPAC codes, which become
+/// `N`. [`PackedSequence::to_acgt_packed`] converts to the base order of
+/// [`encode_base`](crate::encode_base) for k-mer code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedSequence {
+    len: usize,
+    packed: Vec<u8>,
+    n_runs: Vec<Range<usize>>,
+    mask_runs: Vec<Range<usize>>,
+}
+
+fn extend_run(runs: &mut Vec<Range<usize>>, pos: usize) {
+    match runs.last_mut() {
+        Some(run) if run.end == pos => run.end += 1,
+        _ => runs.push(pos..pos + 1),
+    }
+}
+
+impl PackedSequence {
+    /// Creates an empty sequence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Packs `seq`.
+    pub fn from_bytes(seq: &[u8]) -> Self {
+        let mut packed = Self::new();
+        packed.push(seq);
+        packed
+    }
+
+    /// Assembles a sequence from already packed parts. Fails unless
+    /// `packed` holds at least `len` bases and the runs are sorted,
+    /// non-overlapping and within `len`.
+    pub fn from_parts(
+        len: usize,
+        packed: Vec<u8>,
+        n_runs: Vec<Range<usize>>,
+        mask_runs: Vec<Range<usize>>,
+    ) -> Result<Self, ReaderError> {
+        if packed.len() < len.div_ceil(4) {
+            return Err(ReaderError::InvalidFormat {
+                message: format!(
+                    "Packed sequence of {} bytes is too short for {len} bases",
+                    packed.len()
+                ),
+            });
+        }
+        for runs in [&n_runs, &mask_runs] {
+            let mut end = 0;
+            for run in runs {
+                if run.start < end || run.start > run.end || run.end > len {
+                    return Err(ReaderError::InvalidFormat {
+                        message: format!(
+                            "Invalid run {}..{} in packed sequence of length {len}",
+                            run.start, run.end
+                        ),
+                    });
+                }
+                end = run.end;
+            }
+        }
+        Ok(Self {
+            len,
+            packed,
+            n_runs,
+            mask_runs,
+        })
+    }
+
+    /// Appends bases.
+    pub fn push(&mut self, seq: &[u8]) {
+        for &b in seq {
+            let code = match b {
+                b'T' | b't' => 0,
+                b'C' | b'c' => 1,
+                b'A' | b'a' => 2,
+                b'G' | b'g' => 3,
+                _ => {
+                    extend_run(&mut self.n_runs, self.len);
+                    0
+                }
+            };
+            if b.is_ascii_lowercase() {
+                extend_run(&mut self.mask_runs, self.len);
+            }
+            if self.len.is_multiple_of(4) {
+                self.packed.push(0);
+            }
+            *self.packed.last_mut().unwrap() |= code << (6 - 2 * (self.len % 4));
+            self.len += 1;
+        }
+    }
+
+    /// Removes all bases, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.packed.clear();
+        self.n_runs.clear();
+        self.mask_runs.clear();
+    }
+
+    /// Number of bases.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bases.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Packed bases; `N` positions hold `T`.
+    pub fn packed(&self) -> &[u8] {
+        &self.packed
+    }
+
+    /// Packed bases in `A=0, C=1, G=2, T=3` order, the order of
+    /// [`encode_base`](crate::encode_base), with the same layout as
+    /// [`packed`](Self::packed). `N` positions hold `T`.
+    pub fn to_acgt_packed(&self) -> Vec<u8> {
+        self.packed.iter().map(|&b| TO_ACGT[b as usize]).collect()
+    }
+
+    /// Runs of `N`, in order.
+    pub fn n_runs(&self) -> &[Range<usize>] {
+        &self.n_runs
+    }
+
+    /// Runs of lowercase bases, in order.
+    pub fn mask_runs(&self) -> &[Range<usize>] {
+        &self.mask_runs
+    }
+
+    /// Appends the decoded bases to `out`.
+    pub fn decode(&self, out: &mut Vec<u8>) {
+        self.decode_range(0..self.len, out);
+    }
+
+    /// Appends the decoded bases in `range` to `out`.
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn decode_range(&self, range: Range<usize>, out: &mut Vec<u8>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "range {range:?} out of bounds for sequence of length {}",
+            self.len
+        );
+        let start = out.len();
+        out.extend(range.clone().map(|i| {
+            let code = (self.packed[i / 4] >> (6 - 2 * (i % 4))) & 3;
+            BASES[code as usize]
+        }));
+
+        let offset = |run: &Range<usize>| {
+            run.start.max(range.start) - range.start + start
+                ..run.end.min(range.end).max(range.start) - range.start + start
+        };
+        for run in overlapping(&self.n_runs, &range) {
+            out[offset(run)].fill(b'N');
+        }
+        for run in overlapping(&self.mask_runs, &range) {
+            out[offset(run)].make_ascii_lowercase();
+        }
+    }
+}
+
+/// Runs of a sorted run list that overlap `range`.
+fn overlapping<'a>(
+    runs: &'a [Range<usize>],
+    range: &Range<usize>,
+) -> impl Iterator<Item = &'a Range<usize>> {
+    let first = runs.partition_point(|run| run.end <= range.start);
+    let end = range.end;
+    runs[first..].iter().take_while(move |run| run.start < end)
+}
+
+/// Record with a packed sequence.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackedRecord {
+    pub id: Vec<u8>,
+    pub seq: PackedSequence,
+}
+
+/// Iterator packing each record of an event stream.
+pub struct PackedRecords<E> {
+    reader: E,
+    record: PackedRecord,
+    started: bool,
+}
+
+impl<E: EventReader> PackedRecords<E> {
+    /// Packs the records of `reader`.
+    pub fn new(reader: E) -> Self {
+        Self {
+            reader,
+            record: PackedRecord::default(),
+            started: false,
+        }
+    }
+
+    /// Returns the reader.
+    pub fn into_inner(self) -> E {
+        self.reader
+    }
+}
+
+impl<E: EventReader> Iterator for PackedRecords<E> {
+    type Item = Result<PackedRecord, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.reader.next_event() {
+                None => {
+                    let started = std::mem::replace(&mut self.started, false);
+                    return started.then(|| Ok(std::mem::take(&mut self.record)));
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(Event::NextRecord)) => {
+                    return Some(Ok(std::mem::take(&mut self.record)));
+                }
+                Some(Ok(Event::IdChunk(chunk))) => {
+                    self.started = true;
+                    self.record.id.extend_from_slice(chunk);
+                }
+                Some(Ok(Event::SeqChunk(chunk))) => {
+                    self.started = true;
+                    self.record.seq.push(chunk);
+                }
+                Some(Ok(Event::QualChunk(_))) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastaReader;
+
+    fn decoded(seq: &PackedSequence) -> Vec<u8> {
+        let mut out = Vec::new();
+        seq.decode(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_pack_layout() {
+        let seq = PackedSequence::from_bytes(b"TCAGG");
+        assert_eq!(seq.packed(), &[0b00_01_10_11, 0b11_00_00_00]);
+        assert_eq!(seq.len(), 5);
+        assert!(seq.n_runs().is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_with_runs() {
+        let input = b"NNACgtacGTNNNNacRgt";
+        let seq = PackedSequence::from_bytes(input);
+        assert_eq!(seq.n_runs(), &[0..2, 10..14, 16..17]);
+        assert_eq!(seq.mask_runs(), &[4..8, 14..16, 17..19]);
+        assert_eq!(decoded(&seq), b"NNACgtacGTNNNNacNgt");
+
+        let mut out = b"x".to_vec();
+        seq.decode_range(5..12, &mut out);
+        assert_eq!(out, b"xtacGTNN");
+        out.clear();
+        seq.decode_range(3..3, &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_from_parts_checks_lengths() {
+        let seq = PackedSequence::from_bytes(b"ACGTNac");
+        let parts = |len, n_runs: Vec<Range<usize>>| {
+            let mask_runs = seq.mask_runs().to_vec();
+            PackedSequence::from_parts(len, seq.packed().to_vec(), n_runs, mask_runs)
+        };
+        assert_eq!(parts(7, seq.n_runs().to_vec()).unwrap(), seq);
+        assert!(parts(9, seq.n_runs().to_vec()).is_err());
+        assert!(parts(7, vec![4..5, 6..8]).is_err());
+        assert!(parts(7, vec![4..5, 2..3]).is_err());
+    }
+
+    #[test]
+    fn test_acgt_order_matches_kmer_encoding() {
+        let bases = b"ACGTTGCAacgtG";
+        let acgt = PackedSequence::from_bytes(bases).to_acgt_packed();
+        for (i, &base) in bases.iter().enumerate() {
+            let code = (acgt[i / 4] >> (6 - 2 * (i % 4))) & 3;
+            assert_eq!(Some(code as u64), crate::encode_base(base));
+        }
+    }
+
+    #[test]
+    fn test_packed_records() {
+        let data = b">chr1 desc\nACGTNN\nacgt\n>chr2\n\n>chr3\nGGCC\n";
+        let records: Vec<_> = PackedRecords::new(FastaReader::with_capacity(3, &data[..]))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].id, b"chr1 desc");
+        assert_eq!(decoded(&records[0].seq), b"ACGTNNacgt");
+        assert!(records[1].seq.is_empty());
+        assert_eq!(records[2].seq, PackedSequence::from_bytes(b"GGCC"));
+    }
+}
@@ -0,0 +1,260 @@
+use std::io::{Read, Write};
+
+use crate::error::ReaderError;
+use crate::fastq::FastqReader;
+use crate::record::{Record, RecordReader};
+use crate::writer::FastqWriter;
+
+/// Strips a trailing `/1` or `/2` mate suffix from a read name.
+pub fn mate_name(name: &[u8]) -> &[u8] {
+    match name {
+        [rest @ .., b'/', b'1' | b'2'] => rest,
+        _ => name,
+    }
+}
+
+/// Checks that two mates share a read name. Casava comments are not part of
+/// the name, so only `/1` `/2` suffixes need stripping.
+pub(crate) fn check_mates(r1: &Record, r2: &Record, record: usize) -> Result<(), ReaderError> {
+    let (name1, name2) = (mate_name(r1.name()), mate_name(r2.name()));
+    if name1 != name2 {
+        return Err(ReaderError::PairMismatch {
+            record,
+            message: format!(
+                "read names differ: '{}' vs '{}'",
+                String::from_utf8_lossy(r1.name()),
+                String::from_utf8_lossy(r2.name())
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Reads R1/R2 FASTQ files in lockstep.
+pub struct PairedFastqReader<R1, R2> {
+    r1: RecordReader<FastqReader<R1>>,
+    r2: RecordReader<FastqReader<R2>>,
+    index: usize,
+}
+
+impl<R1: Read, R2: Read> PairedFastqReader<R1, R2> {
+    /// Pairs two FASTQ readers.
+    pub fn new(r1: FastqReader<R1>, r2: FastqReader<R2>) -> Self {
+        Self {
+            r1: RecordReader::new(r1),
+            r2: RecordReader::new(r2),
+            index: 0,
+        }
+    }
+
+    /// Reads the next pair into `r1` and `r2`. Returns `false` when both
+    /// files end together; errors if only one ends or names diverge.
+    pub fn read_pair(&mut self, r1: &mut Record, r2: &mut Record) -> Result<bool, ReaderError> {
+        let has1 = self.r1.read_record(r1)?;
+        let has2 = self.r2.read_record(r2)?;
+        let record = self.index;
+
+        match (has1, has2) {
+            (false, false) => return Ok(false),
+            (true, false) => {
+                return Err(ReaderError::PairMismatch {
+                    record,
+                    message: "R2 ended before R1".to_string(),
+                });
+            }
+            (false, true) => {
+                return Err(ReaderError::PairMismatch {
+                    record,
+                    message: "R1 ended before R2".to_string(),
+                });
+            }
+            (true, true) => {}
+        }
+
+        check_mates(r1, r2, record)?;
+        self.index += 1;
+        Ok(true)
+    }
+
+    /// Number of pairs read so far.
+    pub fn pairs_read(&self) -> usize {
+        self.index
+    }
+}
+
+/// Reads mate pairs from a single interleaved FASTQ stream.
+pub struct InterleavedFastqReader<R> {
+    reader: RecordReader<FastqReader<R>>,
+    index: usize,
+}
+
+impl<R: Read> InterleavedFastqReader<R> {
+    /// Wraps a FASTQ reader whose records alternate R1, R2.
+    pub fn new(reader: FastqReader<R>) -> Self {
+        Self {
+            reader: RecordReader::new(reader),
+            index: 0,
+        }
+    }
+
+    /// Reads the next pair into `r1` and `r2`. Returns `false` at EOF;
+    /// errors if the last mate is missing or names diverge.
+    pub fn read_pair(&mut self, r1: &mut Record, r2: &mut Record) -> Result<bool, ReaderError> {
+        if !self.reader.read_record(r1)? {
+            return Ok(false);
+        }
+        if !self.reader.read_record(r2)? {
+            return Err(ReaderError::PairMismatch {
+                record: self.index,
+                message: "stream ended before the second mate".to_string(),
+            });
+        }
+
+        check_mates(r1, r2, self.index)?;
+        self.index += 1;
+        Ok(true)
+    }
+
+    /// Number of pairs read so far.
+    pub fn pairs_read(&self) -> usize {
+        self.index
+    }
+}
+
+/// Writes pairs from `reader` to `writer` as alternating R1, R2 records.
+/// Returns the number of pairs written.
+pub fn interleave<R1: Read, R2: Read, W: Write>(
+    reader: &mut PairedFastqReader<R1, R2>,
+    writer: &mut FastqWriter<W>,
+) -> Result<usize, ReaderError> {
+    let (mut r1, mut r2) = (Record::new(), Record::new());
+    let mut pairs = 0;
+
+    while reader.read_pair(&mut r1, &mut r2)? {
+        writer.write_record(&r1)?;
+        writer.write_record(&r2)?;
+        pairs += 1;
+    }
+
+    writer.flush()?;
+    Ok(pairs)
+}
+
+/// Splits an interleaved stream into separate R1 and R2 writers.
+/// Returns the number of pairs written.
+pub fn deinterleave<R: Read, W1: Write, W2: Write>(
+    reader: &mut InterleavedFastqReader<R>,
+    writer1: &mut FastqWriter<W1>,
+    writer2: &mut FastqWriter<W2>,
+) -> Result<usize, ReaderError> {
+    let (mut r1, mut r2) = (Record::new(), Record::new());
+    let mut pairs = 0;
+
+    while reader.read_pair(&mut r1, &mut r2)? {
+        writer1.write_record(&r1)?;
+        writer2.write_record(&r2)?;
+        pairs += 1;
+    }
+
+    writer1.flush()?;
+    writer2.flush()?;
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn paired<'a>(
+        r1: &'a [u8],
+        r2: &'a [u8],
+    ) -> PairedFastqReader<Cursor<&'a [u8]>, Cursor<&'a [u8]>> {
+        PairedFastqReader::new(
+            FastqReader::new(Cursor::new(r1)),
+            FastqReader::new(Cursor::new(r2)),
+        )
+    }
+
+    #[test]
+    fn test_mate_name() {
+        assert_eq!(mate_name(b"read1/1"), b"read1");
+        assert_eq!(mate_name(b"read1/2"), b"read1");
+        assert_eq!(mate_name(b"read1/3"), b"read1/3");
+        assert_eq!(mate_name(b"read1"), b"read1");
+    }
+
+    #[test]
+    fn test_read_pairs() {
+        let r1 = b"@a/1\nACGT\n+\nIIII\n@b 1:N:0:ACGT\nTT\n+\nII\n";
+        let r2 = b"@a/2\nTGCA\n+\nIIII\n@b 2:N:0:ACGT\nAA\n+\nII\n";
+        let mut reader = paired(r1, r2);
+        let (mut a, mut b) = (Record::new(), Record::new());
+
+        assert!(reader.read_pair(&mut a, &mut b).unwrap());
+        assert_eq!(&a.seq, b"ACGT");
+        assert_eq!(&b.seq, b"TGCA");
+        assert!(reader.read_pair(&mut a, &mut b).unwrap());
+        assert!(!reader.read_pair(&mut a, &mut b).unwrap());
+        assert_eq!(reader.pairs_read(), 2);
+    }
+
+    #[test]
+    fn test_name_mismatch() {
+        let r1 = b"@a\nACGT\n+\nIIII\n@b\nTT\n+\nII\n";
+        let r2 = b"@a\nTGCA\n+\nIIII\n@c\nAA\n+\nII\n";
+        let mut reader = paired(r1, r2);
+        let (mut a, mut b) = (Record::new(), Record::new());
+
+        assert!(reader.read_pair(&mut a, &mut b).unwrap());
+        let err = reader.read_pair(&mut a, &mut b).unwrap_err();
+        assert!(matches!(err, ReaderError::PairMismatch { record: 1, .. }));
+    }
+
+    #[test]
+    fn test_early_end() {
+        let r1 = b"@a\nACGT\n+\nIIII\n@b\nTT\n+\nII\n";
+        let r2 = b"@a\nTGCA\n+\nIIII\n";
+        let mut reader = paired(r1, r2);
+        let (mut a, mut b) = (Record::new(), Record::new());
+
+        assert!(reader.read_pair(&mut a, &mut b).unwrap());
+        let err = reader.read_pair(&mut a, &mut b).unwrap_err();
+        assert!(matches!(err, ReaderError::PairMismatch { record: 1, .. }));
+    }
+
+    #[test]
+    fn test_interleave_round_trip() {
+        let r1 = b"@a/1\nACGT\n+\nIIII\n@b/1\nTT\n+\nII\n";
+        let r2 = b"@a/2\nTGCA\n+\nHHHH\n@b/2\nAA\n+\nHH\n";
+        let mut writer = FastqWriter::new(Vec::new());
+        assert_eq!(interleave(&mut paired(r1, r2), &mut writer).unwrap(), 2);
+        let interleaved = writer.into_inner().unwrap();
+        assert_eq!(
+            &interleaved,
+            b"@a/1\nACGT\n+\nIIII\n@a/2\nTGCA\n+\nHHHH\n@b/1\nTT\n+\nII\n@b/2\nAA\n+\nHH\n"
+        );
+
+        let mut reader =
+            InterleavedFastqReader::new(FastqReader::new(Cursor::new(&interleaved[..])));
+        let mut writer1 = FastqWriter::new(Vec::new());
+        let mut writer2 = FastqWriter::new(Vec::new());
+        assert_eq!(
+            deinterleave(&mut reader, &mut writer1, &mut writer2).unwrap(),
+            2
+        );
+        assert_eq!(&writer1.into_inner().unwrap(), r1);
+        assert_eq!(&writer2.into_inner().unwrap(), r2);
+    }
+
+    #[test]
+    fn test_interleaved_missing_mate() {
+        let data = b"@a/1\nACGT\n+\nIIII\n@a/2\nTGCA\n+\nIIII\n@b/1\nTT\n+\nII\n";
+        let mut reader = InterleavedFastqReader::new(FastqReader::new(Cursor::new(&data[..])));
+        let (mut a, mut b) = (Record::new(), Record::new());
+
+        assert!(reader.read_pair(&mut a, &mut b).unwrap());
+        let err = reader.read_pair(&mut a, &mut b).unwrap_err();
+        assert!(matches!(err, ReaderError::PairMismatch { record: 1, .. }));
+    }
+}
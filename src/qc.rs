@@ -0,0 +1,534 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+use crate::error::ReaderError;
+use crate::event::EventReader;
+use crate::quality::QualityEncoding;
+use crate::record::{Record, RecordReader};
+
+const MAX_PHRED: usize = 93;
+
+/// Base counts at one read position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BaseCounts {
+    pub a: u64,
+    pub c: u64,
+    pub g: u64,
+    pub t: u64,
+    pub n: u64,
+}
+
+/// Quality distribution at one read position, as in FastQC's box plot.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PositionQuality {
+    pub mean: f64,
+    pub median: u8,
+    pub lower_quartile: u8,
+    pub upper_quartile: u8,
+    pub p10: u8,
+    pub p90: u8,
+}
+
+/// Sequence seen in more than the overrepresentation threshold of reads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overrepresented {
+    pub sequence: Vec<u8>,
+    pub count: u64,
+    /// Fraction of all records.
+    pub fraction: f64,
+}
+
+/// Result of [`Qc::report`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QcReport {
+    pub records: u64,
+    pub base_content: Vec<BaseCounts>,
+    /// Empty for records without qualities.
+    pub position_quality: Vec<PositionQuality>,
+    /// Reads per GC percentage, 0 to 100.
+    pub gc_distribution: Vec<u64>,
+    /// `(length, reads)` in increasing length.
+    pub length_distribution: Vec<(usize, u64)>,
+    /// Estimated fraction of reads that are duplicates.
+    pub duplicate_fraction: f64,
+    /// Most frequent first.
+    pub overrepresented: Vec<Overrepresented>,
+}
+
+/// FastQC-style quality control accumulated over records.
+///
+/// Per-position base content and quality cover only the first
+/// `max_positions` bases of each read (1,000 by default), so that long reads
+/// do not grow them without bound; each position costs about 800 bytes.
+/// Length and GC statistics use whole reads.
+///
+/// Duplication is estimated FastQC's way: only the first `max_tracked`
+/// distinct sequences are counted, and reads longer than 75 bases are
+/// truncated to 50 before comparison.
+#[derive(Debug, Clone)]
+pub struct Qc {
+    offset: u8,
+    max_positions: usize,
+    max_tracked: usize,
+    threshold: f64,
+    records: u64,
+    bases: Vec<BaseCounts>,
+    qualities: Vec<[u64; MAX_PHRED + 1]>,
+    gc: [u64; 101],
+    lengths: BTreeMap<usize, u64>,
+    sequences: HashMap<Vec<u8>, u64>,
+    tracked: u64,
+}
+
+impl Default for Qc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Qc {
+    /// Creates an empty collector for Phred+33 data, tracking 100,000
+    /// sequences and reporting those above 0.1% of reads.
+    pub fn new() -> Self {
+        Self {
+            offset: QualityEncoding::Phred33.offset(),
+            max_positions: 1000,
+            max_tracked: 100_000,
+            threshold: 0.001,
+            records: 0,
+            bases: Vec::new(),
+            qualities: Vec::new(),
+            gc: [0; 101],
+            lengths: BTreeMap::new(),
+            sequences: HashMap::new(),
+            tracked: 0,
+        }
+    }
+
+    /// Sets the ASCII offset of quality characters.
+    pub fn with_offset(mut self, offset: u8) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets how many leading read positions per-position metrics cover.
+    pub fn with_max_positions(mut self, max_positions: usize) -> Self {
+        self.max_positions = max_positions;
+        self
+    }
+
+    /// Sets how many distinct sequences are tracked for duplication.
+    pub fn with_max_tracked(mut self, max_tracked: usize) -> Self {
+        self.max_tracked = max_tracked;
+        self
+    }
+
+    /// Sets the read fraction above which a sequence is overrepresented.
+    pub fn with_overrepresented_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Adds one record.
+    pub fn observe(&mut self, record: &Record) {
+        let len = record.seq.len();
+        self.records += 1;
+        *self.lengths.entry(len).or_insert(0) += 1;
+
+        let positions = len.min(self.max_positions);
+        if self.bases.len() < positions {
+            self.bases.resize(positions, BaseCounts::default());
+        }
+        for (counts, &b) in self.bases.iter_mut().zip(&record.seq) {
+            match b {
+                b'A' | b'a' => counts.a += 1,
+                b'C' | b'c' => counts.c += 1,
+                b'G' | b'g' => counts.g += 1,
+                b'T' | b't' => counts.t += 1,
+                b'N' | b'n' => counts.n += 1,
+                _ => {}
+            }
+        }
+
+        let (mut gc, mut acgt) = (0u64, 0u64);
+        for &b in &record.seq {
+            match b {
+                b'C' | b'c' | b'G' | b'g' => {
+                    gc += 1;
+                    acgt += 1;
+                }
+                b'A' | b'a' | b'T' | b't' => acgt += 1,
+                _ => {}
+            }
+        }
+        if let Some(percent) = (gc * 100 + acgt / 2).checked_div(acgt) {
+            self.gc[percent as usize] += 1;
+        }
+
+        let positions = record.qual.len().min(self.max_positions);
+        if self.qualities.len() < positions {
+            self.qualities.resize(positions, [0; MAX_PHRED + 1]);
+        }
+        for (hist, &q) in self.qualities.iter_mut().zip(&record.qual) {
+            hist[(q.saturating_sub(self.offset) as usize).min(MAX_PHRED)] += 1;
+        }
+
+        let key = if len > 75 {
+            &record.seq[..50]
+        } else {
+            &record.seq[..]
+        };
+        if let Some(count) = self.sequences.get_mut(key) {
+            *count += 1;
+            self.tracked += 1;
+        } else if self.sequences.len() < self.max_tracked {
+            self.sequences.insert(key.to_vec(), 1);
+            self.tracked += 1;
+        }
+    }
+
+    /// Adds every remaining record of `reader`.
+    pub fn run<E: EventReader>(&mut self, reader: &mut RecordReader<E>) -> Result<(), ReaderError> {
+        let mut record = Record::new();
+        while reader.read_record(&mut record)? {
+            self.observe(&record);
+        }
+        Ok(())
+    }
+
+    /// Computes the report of everything observed so far.
+    pub fn report(&self) -> QcReport {
+        let mut overrepresented: Vec<_> = self
+            .sequences
+            .iter()
+            .map(|(seq, &count)| Overrepresented {
+                sequence: seq.clone(),
+                count,
+                fraction: count as f64 / self.records as f64,
+            })
+            .filter(|o| o.fraction > self.threshold)
+            .collect();
+        overrepresented.sort_by(|a, b| b.count.cmp(&a.count).then(a.sequence.cmp(&b.sequence)));
+
+        QcReport {
+            records: self.records,
+            base_content: self.bases.clone(),
+            position_quality: self.qualities.iter().map(position_quality).collect(),
+            gc_distribution: self.gc.to_vec(),
+            length_distribution: self.lengths.iter().map(|(&l, &n)| (l, n)).collect(),
+            duplicate_fraction: if self.tracked > 0 {
+                1.0 - self.sequences.len() as f64 / self.tracked as f64
+            } else {
+                0.0
+            },
+            overrepresented,
+        }
+    }
+}
+
+fn position_quality(hist: &[u64; MAX_PHRED + 1]) -> PositionQuality {
+    let total: u64 = hist.iter().sum();
+    let sum: u64 = hist.iter().enumerate().map(|(q, &n)| q as u64 * n).sum();
+    let percentile = |p: f64| {
+        let target = ((total as f64 * p).ceil() as u64).max(1);
+        let mut acc = 0;
+        for (q, &n) in hist.iter().enumerate() {
+            acc += n;
+            if acc >= target {
+                return q as u8;
+            }
+        }
+        0
+    };
+
+    PositionQuality {
+        mean: sum as f64 / total as f64,
+        median: percentile(0.5),
+        lower_quartile: percentile(0.25),
+        upper_quartile: percentile(0.75),
+        p10: percentile(0.1),
+        p90: percentile(0.9),
+    }
+}
+
+fn write_json_string<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    writer.write_all(b"\"")?;
+    for c in String::from_utf8_lossy(bytes).chars() {
+        match c {
+            '"' => writer.write_all(b"\\\"")?,
+            '\\' => writer.write_all(b"\\\\")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{c}")?,
+        }
+    }
+    writer.write_all(b"\"")
+}
+
+fn write_html_escaped<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    for c in String::from_utf8_lossy(bytes).chars() {
+        match c {
+            '<' => writer.write_all(b"&lt;")?,
+            '>' => writer.write_all(b"&gt;")?,
+            '&' => writer.write_all(b"&amp;")?,
+            c => write!(writer, "{c}")?,
+        }
+    }
+    Ok(())
+}
+
+impl QcReport {
+    /// Writes the report as a JSON object.
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{{")?;
+        writeln!(writer, "  \"records\": {},", self.records)?;
+
+        write!(writer, "  \"length_distribution\": [")?;
+        for (i, (len, n)) in self.length_distribution.iter().enumerate() {
+            let sep = if i > 0 { ", " } else { "" };
+            write!(writer, "{sep}[{len}, {n}]")?;
+        }
+        writeln!(writer, "],")?;
+
+        write!(writer, "  \"base_content\": [")?;
+        for (i, c) in self.base_content.iter().enumerate() {
+            let sep = if i > 0 { "," } else { "" };
+            write!(
+                writer,
+                "{sep}\n    {{\"A\": {}, \"C\": {}, \"G\": {}, \"T\": {}, \"N\": {}}}",
+                c.a, c.c, c.g, c.t, c.n
+            )?;
+        }
+        writeln!(writer, "\n  ],")?;
+
+        write!(writer, "  \"position_quality\": [")?;
+        for (i, q) in self.position_quality.iter().enumerate() {
+            let sep = if i > 0 { "," } else { "" };
+            write!(
+                writer,
+                "{sep}\n    {{\"mean\": {}, \"median\": {}, \"lower_quartile\": {}, \
+                 \"upper_quartile\": {}, \"p10\": {}, \"p90\": {}}}",
+                q.mean, q.median, q.lower_quartile, q.upper_quartile, q.p10, q.p90
+            )?;
+        }
+        writeln!(writer, "\n  ],")?;
+
+        write!(writer, "  \"gc_distribution\": [")?;
+        for (i, n) in self.gc_distribution.iter().enumerate() {
+            let sep = if i > 0 { ", " } else { "" };
+            write!(writer, "{sep}{n}")?;
+        }
+        writeln!(writer, "],")?;
+
+        writeln!(
+            writer,
+            "  \"duplicate_fraction\": {},",
+            self.duplicate_fraction
+        )?;
+
+        write!(writer, "  \"overrepresented\": [")?;
+        for (i, o) in self.overrepresented.iter().enumerate() {
+            let sep = if i > 0 { "," } else { "" };
+            write!(writer, "{sep}\n    {{\"sequence\": ")?;
+            write_json_string(writer, &o.sequence)?;
+            write!(
+                writer,
+                ", \"count\": {}, \"fraction\": {}}}",
+                o.count, o.fraction
+            )?;
+        }
+        writeln!(writer, "\n  ]")?;
+        writeln!(writer, "}}")
+    }
+
+    /// Writes the report as a standalone HTML page of tables.
+    pub fn write_html<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "<!DOCTYPE html>")?;
+        writeln!(
+            writer,
+            "<html><head><meta charset=\"utf-8\"><title>QC report</title>"
+        )?;
+        writeln!(
+            writer,
+            "<style>table{{border-collapse:collapse}}td,th{{border:1px solid #ccc;\
+             padding:2px 6px;text-align:right}}</style></head><body>"
+        )?;
+
+        writeln!(writer, "<h1>QC report</h1>")?;
+        writeln!(writer, "<table>")?;
+        writeln!(writer, "<tr><th>Records</th><td>{}</td></tr>", self.records)?;
+        writeln!(
+            writer,
+            "<tr><th>Duplicates</th><td>{:.2}%</td></tr>",
+            self.duplicate_fraction * 100.0
+        )?;
+        writeln!(writer, "</table>")?;
+
+        writeln!(writer, "<h2>Per-position quality</h2>")?;
+        writeln!(
+            writer,
+            "<table><tr><th>Position</th><th>Mean</th><th>Median</th><th>Q1</th>\
+             <th>Q3</th><th>10%</th><th>90%</th></tr>"
+        )?;
+        for (i, q) in self.position_quality.iter().enumerate() {
+            writeln!(
+                writer,
+                "<tr><td>{}</td><td>{:.2}</td><td>{}</td><td>{}</td><td>{}</td>\
+                 <td>{}</td><td>{}</td></tr>",
+                i + 1,
+                q.mean,
+                q.median,
+                q.lower_quartile,
+                q.upper_quartile,
+                q.p10,
+                q.p90
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+
+        writeln!(writer, "<h2>Per-position base content</h2>")?;
+        writeln!(
+            writer,
+            "<table><tr><th>Position</th><th>A</th><th>C</th><th>G</th><th>T</th>\
+             <th>N</th></tr>"
+        )?;
+        for (i, c) in self.base_content.iter().enumerate() {
+            writeln!(
+                writer,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                i + 1,
+                c.a,
+                c.c,
+                c.g,
+                c.t,
+                c.n
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+
+        writeln!(writer, "<h2>Length distribution</h2>")?;
+        writeln!(writer, "<table><tr><th>Length</th><th>Reads</th></tr>")?;
+        for (len, n) in &self.length_distribution {
+            writeln!(writer, "<tr><td>{len}</td><td>{n}</td></tr>")?;
+        }
+        writeln!(writer, "</table>")?;
+
+        writeln!(writer, "<h2>GC distribution</h2>")?;
+        writeln!(writer, "<table><tr><th>GC %</th><th>Reads</th></tr>")?;
+        for (gc, n) in self.gc_distribution.iter().enumerate() {
+            if *n > 0 {
+                writeln!(writer, "<tr><td>{gc}</td><td>{n}</td></tr>")?;
+            }
+        }
+        writeln!(writer, "</table>")?;
+
+        writeln!(writer, "<h2>Overrepresented sequences</h2>")?;
+        writeln!(
+            writer,
+            "<table><tr><th>Sequence</th><th>Count</th><th>Percentage</th></tr>"
+        )?;
+        for o in &self.overrepresented {
+            write!(writer, "<tr><td style=\"text-align:left\"><code>")?;
+            write_html_escaped(writer, &o.sequence)?;
+            writeln!(
+                writer,
+                "</code></td><td>{}</td><td>{:.2}%</td></tr>",
+                o.count,
+                o.fraction * 100.0
+            )?;
+        }
+        writeln!(writer, "</table>")?;
+        writeln!(writer, "</body></html>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastqReader;
+    use std::io::Cursor;
+
+    fn run(data: &[u8], qc: Qc) -> QcReport {
+        let mut qc = qc;
+        let mut reader = RecordReader::new(FastqReader::new(Cursor::new(data)));
+        qc.run(&mut reader).unwrap();
+        qc.report()
+    }
+
+    #[test]
+    fn test_position_metrics() {
+        let data = b"@r1\nACGT\n+\nIIII\n@r2\nAGGN\n+\n5555\n@r3\nAC\n+\n+I\n";
+        let report = run(data, Qc::new());
+
+        assert_eq!(report.records, 3);
+        assert_eq!(report.length_distribution, vec![(2, 1), (4, 2)]);
+        assert_eq!(report.base_content[0].a, 3);
+        assert_eq!(
+            report.base_content[1],
+            BaseCounts {
+                c: 2,
+                g: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(report.base_content[3].n, 1);
+        assert_eq!(report.position_quality.len(), 4);
+        assert_eq!(report.position_quality[0].median, 20);
+        assert_eq!(report.position_quality[0].p10, 10);
+        assert_eq!(report.position_quality[0].p90, 40);
+        assert!((report.position_quality[1].mean - 100.0 / 3.0).abs() < 1e-12);
+        assert_eq!(report.gc_distribution[50], 2);
+        assert_eq!(report.gc_distribution[67], 1);
+    }
+
+    #[test]
+    fn test_max_positions() {
+        let data = b"@r1\nACGTGG\n+\nIIIIII\n@r2\nAC\n+\nII\n";
+        let report = run(data, Qc::new().with_max_positions(3));
+
+        assert_eq!(report.base_content.len(), 3);
+        assert_eq!(report.position_quality.len(), 3);
+        assert_eq!(report.length_distribution, vec![(2, 1), (6, 1)]);
+        assert_eq!(report.gc_distribution[67], 1);
+        assert_eq!(report.gc_distribution[50], 1);
+    }
+
+    #[test]
+    fn test_duplication_and_overrepresented() {
+        let data = b"@a\nAAAA\n+\nIIII\n@b\nAAAA\n+\nIIII\n@c\nAAAA\n+\nIIII\n@d\nCCCC\n+\nIIII\n";
+        let report = run(data, Qc::new().with_overrepresented_threshold(0.3));
+
+        assert_eq!(report.duplicate_fraction, 0.5);
+        assert_eq!(
+            report.overrepresented,
+            vec![Overrepresented {
+                sequence: b"AAAA".to_vec(),
+                count: 3,
+                fraction: 0.75
+            }]
+        );
+
+        let limited = run(data, Qc::new().with_max_tracked(0));
+        assert_eq!(limited.duplicate_fraction, 0.0);
+        assert!(limited.overrepresented.is_empty());
+    }
+
+    #[test]
+    fn test_json_and_html_output() {
+        let report = run(b"@r1\nACGT\n+\nIIII\n", Qc::new());
+
+        let mut json = Vec::new();
+        report.write_json(&mut json).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.starts_with("{\n  \"records\": 1,\n"));
+        assert!(json.contains("\"length_distribution\": [[4, 1]],"));
+        assert!(json.contains("{\"A\": 1, \"C\": 0, \"G\": 0, \"T\": 0, \"N\": 0}"));
+        assert!(json.contains("{\"sequence\": \"ACGT\", \"count\": 1, \"fraction\": 1}"));
+        assert!(json.ends_with("  ]\n}\n"));
+
+        let mut html = Vec::new();
+        report.write_html(&mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<code>ACGT</code>"));
+    }
+}
@@ -0,0 +1,319 @@
+use std::sync::OnceLock;
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+
+/// ASCII encoding of quality scores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityEncoding {
+    /// Sanger / Illumina 1.8+ (`!` = Q0).
+    Phred33,
+    /// Illumina 1.3-1.7 (`@` = Q0).
+    Phred64,
+    /// Solexa / Illumina 1.0 (`@` = Q0, scores down to -5).
+    Solexa,
+}
+
+impl QualityEncoding {
+    /// ASCII value of score zero.
+    pub fn offset(self) -> u8 {
+        match self {
+            QualityEncoding::Phred33 => 33,
+            QualityEncoding::Phred64 | QualityEncoding::Solexa => 64,
+        }
+    }
+
+    /// Lowest valid ASCII character.
+    pub fn min_char(self) -> u8 {
+        match self {
+            QualityEncoding::Phred33 => b'!',
+            QualityEncoding::Phred64 => b'@',
+            QualityEncoding::Solexa => b';',
+        }
+    }
+
+    /// Highest valid ASCII character.
+    pub fn max_char(self) -> u8 {
+        b'~'
+    }
+
+    /// Returns the position of the first character outside the valid range.
+    #[inline]
+    pub fn find_invalid(self, qual: &[u8]) -> Option<usize> {
+        let (min, max) = (self.min_char(), self.max_char());
+        qual.iter().position(|&b| b < min || b > max)
+    }
+
+    /// Lookup table mapping characters of this encoding to Phred+33.
+    pub fn phred33_table(self) -> [u8; 256] {
+        let mut table = [0u8; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            let byte = i as u8;
+            *entry = match self {
+                QualityEncoding::Phred33 => byte,
+                QualityEncoding::Phred64 => byte.saturating_sub(31),
+                QualityEncoding::Solexa => {
+                    let solexa = byte as f64 - 64.0;
+                    let phred = 10.0 * (10f64.powf(solexa / 10.0) + 1.0).log10();
+                    (phred.round() as u8).saturating_add(33).min(b'~')
+                }
+            };
+        }
+        table
+    }
+}
+
+/// Infers the quality encoding from the range of observed characters.
+#[derive(Debug, Clone, Default)]
+pub struct QualityDetector {
+    min: Option<u8>,
+    max: u8,
+}
+
+impl QualityDetector {
+    /// Creates an empty detector.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a chunk of quality characters.
+    pub fn observe(&mut self, qual: &[u8]) {
+        for &b in qual {
+            self.min = Some(self.min.map_or(b, |m| m.min(b)));
+            self.max = self.max.max(b);
+        }
+    }
+
+    /// Returns the most likely encoding, or `None` if nothing was observed.
+    ///
+    /// All-high scores that fit Phred+33 as well as Phred+64 or Solexa are
+    /// reported as Phred+33 unless a character above `K` (Q42 in Phred+33)
+    /// is seen.
+    pub fn encoding(&self) -> Option<QualityEncoding> {
+        let min = self.min?;
+        Some(if min < b';' || self.max <= b'K' {
+            QualityEncoding::Phred33
+        } else if min < b'@' {
+            QualityEncoding::Solexa
+        } else {
+            QualityEncoding::Phred64
+        })
+    }
+}
+
+/// Scans up to `max_records` records and infers their quality encoding.
+/// The scanned events are consumed from `reader`. Returns `None` without
+/// reading anything if `max_records` is 0.
+pub fn detect_quality_encoding<E: EventReader>(
+    reader: &mut E,
+    max_records: usize,
+) -> Result<Option<QualityEncoding>, ReaderError> {
+    if max_records == 0 {
+        return Ok(None);
+    }
+    let mut detector = QualityDetector::new();
+    let mut records = 1;
+
+    while let Some(event) = reader.next_event() {
+        match event? {
+            Event::NextRecord => {
+                records += 1;
+                if records > max_records {
+                    break;
+                }
+            }
+            Event::QualChunk(chunk) => detector.observe(chunk),
+            Event::IdChunk(_) | Event::SeqChunk(_) => {}
+        }
+    }
+
+    Ok(detector.encoding())
+}
+
+/// Appends numeric scores decoded from ASCII `qual` to `out`.
+pub fn decode_phred(qual: &[u8], offset: u8, out: &mut Vec<u8>) {
+    out.extend(qual.iter().map(|&b| b.saturating_sub(offset)));
+}
+
+/// Error probability of a Phred score, `10^(-q/10)`.
+#[inline]
+pub fn error_probability(q: u8) -> f64 {
+    static TABLE: OnceLock<[f64; 256]> = OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        let mut table = [0.0; 256];
+        for (q, p) in table.iter_mut().enumerate() {
+            *p = 10f64.powf(-(q as f64) / 10.0);
+        }
+        table
+    });
+    table[q as usize]
+}
+
+/// Per-record quality statistics, updated incrementally from `QualChunk`s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityStats {
+    offset: u8,
+    len: usize,
+    sum: u64,
+    expected_errors: f64,
+    min: Option<u8>,
+    q30: usize,
+}
+
+impl Default for QualityStats {
+    fn default() -> Self {
+        Self::new(QualityEncoding::Phred33.offset())
+    }
+}
+
+impl QualityStats {
+    /// Creates empty statistics for characters with the given ASCII offset.
+    pub fn new(offset: u8) -> Self {
+        Self {
+            offset,
+            len: 0,
+            sum: 0,
+            expected_errors: 0.0,
+            min: None,
+            q30: 0,
+        }
+    }
+
+    /// Computes statistics for a whole quality string.
+    pub fn from_qual(qual: &[u8], offset: u8) -> Self {
+        let mut stats = Self::new(offset);
+        stats.update(qual);
+        stats
+    }
+
+    /// Adds a chunk of quality characters.
+    pub fn update(&mut self, qual: &[u8]) {
+        for &b in qual {
+            let q = b.saturating_sub(self.offset);
+            self.sum += q as u64;
+            self.expected_errors += error_probability(q);
+            self.min = Some(self.min.map_or(q, |m| m.min(q)));
+            if q >= 30 {
+                self.q30 += 1;
+            }
+        }
+        self.len += qual.len();
+    }
+
+    /// Clears the statistics for the next record.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.offset);
+    }
+
+    /// Number of scores seen.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no scores were seen.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Mean Phred score, or 0 if empty.
+    pub fn mean(&self) -> f64 {
+        if self.len == 0 {
+            0.0
+        } else {
+            self.sum as f64 / self.len as f64
+        }
+    }
+
+    /// Expected number of base-call errors.
+    pub fn expected_errors(&self) -> f64 {
+        self.expected_errors
+    }
+
+    /// Lowest Phred score, or `None` if empty.
+    pub fn min(&self) -> Option<u8> {
+        self.min
+    }
+
+    /// Number of bases with a score of at least Q30.
+    pub fn q30_count(&self) -> usize {
+        self.q30
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastqReader;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_detect_encodings() {
+        let mut detector = QualityDetector::new();
+        detector.observe(b"#AAFFJJ");
+        assert_eq!(detector.encoding(), Some(QualityEncoding::Phred33));
+
+        let mut detector = QualityDetector::new();
+        detector.observe(b"BBhhgf");
+        assert_eq!(detector.encoding(), Some(QualityEncoding::Phred64));
+
+        let mut detector = QualityDetector::new();
+        detector.observe(b";<hh");
+        assert_eq!(detector.encoding(), Some(QualityEncoding::Solexa));
+
+        // High-quality Phred+33 whose minimum falls in the Solexa-only range.
+        let mut detector = QualityDetector::new();
+        detector.observe(b"<<?FFJJJ");
+        assert_eq!(detector.encoding(), Some(QualityEncoding::Phred33));
+
+        assert_eq!(QualityDetector::new().encoding(), None);
+    }
+
+    #[test]
+    fn test_detect_from_reader() {
+        let data = b"@r1\nACGT\n+\nhhhh\n@r2\nACGT\n+\nBBBB\n@r3\nACGT\n+\n!!!!\n";
+        let mut reader = FastqReader::new(Cursor::new(&data[..]));
+        let encoding = detect_quality_encoding(&mut reader, 2).unwrap();
+        assert_eq!(encoding, Some(QualityEncoding::Phred64));
+
+        let mut reader = FastqReader::new(Cursor::new(&data[..]));
+        assert_eq!(detect_quality_encoding(&mut reader, 0).unwrap(), None);
+        assert!(matches!(reader.next_event(), Some(Ok(Event::IdChunk(b"r1")))));
+    }
+
+    #[test]
+    fn test_phred33_table() {
+        let table = QualityEncoding::Phred64.phred33_table();
+        assert_eq!(table[b'@' as usize], b'!');
+        assert_eq!(table[b'h' as usize], b'I');
+
+        let table = QualityEncoding::Solexa.phred33_table();
+        assert_eq!(table[b';' as usize], b'"');
+        assert_eq!(table[b'h' as usize], b'I');
+    }
+
+    #[test]
+    fn test_decode_phred() {
+        let mut scores = Vec::new();
+        decode_phred(b"!+5", 33, &mut scores);
+        decode_phred(b"I", 33, &mut scores);
+        assert_eq!(scores, vec![0, 10, 20, 40]);
+    }
+
+    #[test]
+    fn test_quality_stats_across_chunks() {
+        let mut stats = QualityStats::default();
+        stats.update(b"+5");
+        stats.update(b"?I");
+
+        assert_eq!(stats.len(), 4);
+        assert_eq!(stats.mean(), 25.0);
+        assert_eq!(stats.min(), Some(10));
+        assert_eq!(stats.q30_count(), 2);
+        assert!((stats.expected_errors() - 0.1111).abs() < 1e-9);
+
+        stats.reset();
+        assert!(stats.is_empty());
+        assert_eq!(stats.min(), None);
+        assert_eq!(stats.mean(), 0.0);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::util::split_name;
+
+/// Owned record assembled from events. Buffers are reused across reads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    /// Full header line without the leading `>`/`@`.
+    pub id: Vec<u8>,
+    /// Sequence bases.
+    pub seq: Vec<u8>,
+    /// Quality string (empty for FASTA).
+    pub qual: Vec<u8>,
+}
+
+impl Record {
+    /// Creates an empty record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a record by copying a header, sequence and quality string,
+    /// e.g. for records built outside a reader. Pass an empty `qual` for
+    /// FASTA.
+    pub fn from_parts(id: &[u8], seq: &[u8], qual: &[u8]) -> Self {
+        Self {
+            id: id.to_vec(),
+            seq: seq.to_vec(),
+            qual: qual.to_vec(),
+        }
+    }
+
+    /// Clears all buffers, keeping their allocations.
+    pub fn clear(&mut self) {
+        self.id.clear();
+        self.seq.clear();
+        self.qual.clear();
+    }
+
+    /// Record name: the header up to the first space or tab.
+    pub fn name(&self) -> &[u8] {
+        split_name(&self.id).0
+    }
+
+    /// Header text after the name, or `None` if there is none.
+    pub fn description(&self) -> Option<&[u8]> {
+        split_name(&self.id).1
+    }
+}
+
+/// Assembles events from an [`EventReader`] into whole records.
+pub struct RecordReader<E> {
+    inner: E,
+    pending: bool,
+}
+
+impl<E: EventReader> RecordReader<E> {
+    /// Wraps an event reader.
+    pub fn new(inner: E) -> Self {
+        Self {
+            inner,
+            pending: false,
+        }
+    }
+
+    /// Reads the next record into `record`, replacing its contents.
+    /// Returns `false` at EOF.
+    pub fn read_record(&mut self, record: &mut Record) -> Result<bool, ReaderError> {
+        record.clear();
+        let mut found = std::mem::take(&mut self.pending);
+
+        while let Some(event) = self.inner.next_event() {
+            match event? {
+                Event::NextRecord => {
+                    self.pending = true;
+                    return Ok(true);
+                }
+                Event::IdChunk(chunk) => record.id.extend_from_slice(chunk),
+                Event::SeqChunk(chunk) => record.seq.extend_from_slice(chunk),
+                Event::QualChunk(chunk) => record.qual.extend_from_slice(chunk),
+            }
+            found = true;
+        }
+
+        Ok(found)
+    }
+
+    /// Returns a reference to the underlying event reader.
+    pub fn get_ref(&self) -> &E {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying event reader.
+    pub fn get_mut(&mut self) -> &mut E {
+        &mut self.inner
+    }
+
+    /// Unwraps the underlying event reader.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FastaReader, FastqReader};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_fasta_records() {
+        let data = b">seq1 first\nACGT\nTG\n>seq2\nCCCC\n";
+        let mut reader = RecordReader::new(FastaReader::new(Cursor::new(&data[..])));
+        let mut record = Record::new();
+
+        assert!(reader.read_record(&mut record).unwrap());
+        assert_eq!(record.name(), b"seq1");
+        assert_eq!(record.description(), Some(&b"first"[..]));
+        assert_eq!(&record.seq, b"ACGTTG");
+        assert!(record.qual.is_empty());
+
+        assert!(reader.read_record(&mut record).unwrap());
+        assert_eq!(record.name(), b"seq2");
+        assert_eq!(record.description(), None);
+        assert_eq!(&record.seq, b"CCCC");
+
+        assert!(!reader.read_record(&mut record).unwrap());
+    }
+
+    #[test]
+    fn test_fastq_records() {
+        let data = b"@read1\nACGT\n+\nIIII\n@read2\nTG\n+\nHH\n";
+        let mut reader = RecordReader::new(FastqReader::with_capacity(4, Cursor::new(&data[..])));
+        let mut record = Record::new();
+
+        assert!(reader.read_record(&mut record).unwrap());
+        assert_eq!(&record.id, b"read1");
+        assert_eq!(&record.seq, b"ACGT");
+        assert_eq!(&record.qual, b"IIII");
+
+        assert!(reader.read_record(&mut record).unwrap());
+        assert_eq!(&record.id, b"read2");
+        assert_eq!(&record.seq, b"TG");
+        assert_eq!(&record.qual, b"HH");
+
+        assert!(!reader.read_record(&mut record).unwrap());
+    }
+
+    #[test]
+    fn test_empty_last_record() {
+        let data = b">seq1\nACGT\n>\n";
+        let mut reader = RecordReader::new(FastaReader::new(Cursor::new(&data[..])));
+        let mut record = Record::new();
+
+        assert!(reader.read_record(&mut record).unwrap());
+        assert!(reader.read_record(&mut record).unwrap());
+        assert!(record.id.is_empty());
+        assert!(record.seq.is_empty());
+        assert!(!reader.read_record(&mut record).unwrap());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::record::{Record, RecordReader};
+
+const COMPLEMENT: [u8; 256] = build_complement_table();
+
+const fn build_complement_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = i as u8;
+        i += 1;
+    }
+
+    let pairs: [(u8, u8); 12] = [
+        (b'A', b'T'),
+        (b'T', b'A'),
+        (b'U', b'A'),
+        (b'C', b'G'),
+        (b'G', b'C'),
+        (b'R', b'Y'),
+        (b'Y', b'R'),
+        (b'K', b'M'),
+        (b'M', b'K'),
+        (b'B', b'V'),
+        (b'V', b'B'),
+        (b'D', b'H'),
+    ];
+    let mut j = 0;
+    while j < pairs.len() {
+        let (from, to) = pairs[j];
+        table[from as usize] = to;
+        table[from.to_ascii_lowercase() as usize] = to.to_ascii_lowercase();
+        j += 1;
+    }
+    table[b'H' as usize] = b'D';
+    table[b'h' as usize] = b'd';
+    table
+}
+
+/// Complements an IUPAC nucleotide code, preserving case.
+/// `S`, `W`, `N` and non-nucleotide bytes map to themselves.
+#[inline]
+pub fn complement(base: u8) -> u8 {
+    COMPLEMENT[base as usize]
+}
+
+/// Reverse-complements a sequence in place.
+pub fn reverse_complement_in_place(seq: &mut [u8]) {
+    seq.reverse();
+    for base in seq.iter_mut() {
+        *base = complement(*base);
+    }
+}
+
+/// Writes the reverse complement of `seq` into `out`, replacing its contents.
+pub fn reverse_complement(seq: &[u8], out: &mut Vec<u8>) {
+    out.clear();
+    out.extend(seq.iter().rev().map(|&b| complement(b)));
+}
+
+impl Record {
+    /// Reverse-complements the sequence and reverses the quality string.
+    pub fn reverse_complement(&mut self) {
+        reverse_complement_in_place(&mut self.seq);
+        self.qual.reverse();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Fetch,
+    Id,
+    Sequence,
+    Quality,
+}
+
+/// Event adapter that reverse-complements each record.
+///
+/// Records are assembled into a reused buffer, so each record is emitted as
+/// single `IdChunk`/`SeqChunk`/`QualChunk` events.
+pub struct ReverseComplementReader<E> {
+    reader: RecordReader<E>,
+    record: Record,
+    stage: Stage,
+    first_record: bool,
+}
+
+impl<E: EventReader> ReverseComplementReader<E> {
+    /// Wraps an event reader.
+    pub fn new(inner: E) -> Self {
+        Self {
+            reader: RecordReader::new(inner),
+            record: Record::new(),
+            stage: Stage::Fetch,
+            first_record: true,
+        }
+    }
+
+    /// Unwraps the underlying event reader.
+    pub fn into_inner(self) -> E {
+        self.reader.into_inner()
+    }
+
+    /// Returns the next event, or `None` at EOF.
+    pub fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>> {
+        loop {
+            match self.stage {
+                Stage::Fetch => match self.reader.read_record(&mut self.record) {
+                    Ok(true) => {
+                        self.record.reverse_complement();
+                        self.stage = Stage::Id;
+                        if !std::mem::replace(&mut self.first_record, false) {
+                            return Some(Ok(Event::NextRecord));
+                        }
+                    }
+                    Ok(false) => return None,
+                    Err(e) => return Some(Err(e)),
+                },
+                Stage::Id => {
+                    self.stage = Stage::Sequence;
+                    if !self.record.id.is_empty() {
+                        break;
+                    }
+                }
+                Stage::Sequence => {
+                    self.stage = Stage::Quality;
+                    if !self.record.seq.is_empty() {
+                        break;
+                    }
+                }
+                Stage::Quality => {
+                    self.stage = Stage::Fetch;
+                    if !self.record.qual.is_empty() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        // The stage has already advanced past the chunk being emitted.
+        let event = match self.stage {
+            Stage::Sequence => Event::IdChunk(&self.record.id),
+            Stage::Quality => Event::SeqChunk(&self.record.seq),
+            _ => Event::QualChunk(&self.record.qual),
+        };
+        Some(Ok(event))
+    }
+}
+
+impl<E: EventReader> EventReader for ReverseComplementReader<E> {
+    fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>> {
+        ReverseComplementReader::next_event(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FastaReader, FastqReader};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_iupac_and_case() {
+        let mut out = Vec::new();
+        reverse_complement(b"ACGTRYKMBDHVNSWacgtn", &mut out);
+        assert_eq!(&out, b"nacgtWSNBDHVKMRYACGT");
+
+        let mut seq = b"AAcGU".to_vec();
+        reverse_complement_in_place(&mut seq);
+        assert_eq!(&seq, b"ACgTT");
+    }
+
+    #[test]
+    fn test_fastq_record() {
+        let mut record = Record::from_parts(b"read1", b"AACG", b"ABCD");
+        record.reverse_complement();
+        assert_eq!(&record.seq, b"CGTT");
+        assert_eq!(&record.qual, b"DCBA");
+    }
+
+    #[test]
+    fn test_fasta_events() {
+        let data = b">seq1\nAAC\nGT\n>seq2\nTTTG\n";
+        let mut reader = ReverseComplementReader::new(FastaReader::with_capacity(
+            4,
+            Cursor::new(&data[..]),
+        ));
+
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::IdChunk(id) if id == b"seq1"));
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::SeqChunk(s) if s == b"ACGTT"));
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::NextRecord));
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::IdChunk(id) if id == b"seq2"));
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::SeqChunk(s) if s == b"CAAA"));
+        assert!(reader.next_event().is_none());
+    }
+
+    #[test]
+    fn test_fastq_events() {
+        let data = b"@read1\nACGG\n+\nIIHH\n";
+        let mut reader = ReverseComplementReader::new(FastqReader::new(Cursor::new(&data[..])));
+
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::IdChunk(id) if id == b"read1"));
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::SeqChunk(s) if s == b"CCGT"));
+        assert!(matches!(reader.next_event().unwrap().unwrap(), Event::QualChunk(q) if q == b"HHII"));
+        assert!(reader.next_event().is_none());
+    }
+}
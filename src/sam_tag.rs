@@ -0,0 +1,167 @@
+use std::io::{self, Write};
+
+use crate::record::Record;
+use crate::util::parse_num;
+
+/// Typed value of a SAM optional field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamTagValue<'a> {
+    /// `A`: printable character.
+    Char(u8),
+    /// `i`: signed integer.
+    Int(i64),
+    /// `f`: single-precision float.
+    Float(f32),
+    /// `Z`: printable string.
+    String(&'a [u8]),
+    /// `H`: hex-encoded byte array, unparsed.
+    Hex(&'a [u8]),
+    /// `B`: numeric array as written (`type,v1,v2,...`), unparsed.
+    Array(&'a [u8]),
+}
+
+/// SAM optional field `TG:T:value`, borrowed from the header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamTag<'a> {
+    pub tag: [u8; 2],
+    pub value: SamTagValue<'a>,
+}
+
+impl<'a> SamTag<'a> {
+    /// Creates a `Z` (string) tag.
+    pub fn string(tag: [u8; 2], value: &'a [u8]) -> Self {
+        Self {
+            tag,
+            value: SamTagValue::String(value),
+        }
+    }
+
+    /// Parses a single `TG:T:value` token.
+    pub fn parse(token: &'a [u8]) -> Option<Self> {
+        let [t0, t1, b':', ty, b':', value @ ..] = token else {
+            return None;
+        };
+        if !t0.is_ascii_alphabetic() || !t1.is_ascii_alphanumeric() {
+            return None;
+        }
+
+        let value = match ty {
+            b'A' => match value {
+                [c] => SamTagValue::Char(*c),
+                _ => return None,
+            },
+            b'i' => SamTagValue::Int(parse_num(value)?),
+            b'f' => SamTagValue::Float(parse_num(value)?),
+            b'Z' => SamTagValue::String(value),
+            b'H' => SamTagValue::Hex(value),
+            b'B' => SamTagValue::Array(value),
+            _ => return None,
+        };
+
+        Some(Self {
+            tag: [*t0, *t1],
+            value,
+        })
+    }
+
+    /// Writes the tag as `TG:T:value`.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.tag)?;
+        match self.value {
+            SamTagValue::Char(c) => {
+                writer.write_all(b":A:")?;
+                writer.write_all(&[c])
+            }
+            SamTagValue::Int(v) => write!(writer, ":i:{v}"),
+            SamTagValue::Float(v) => write!(writer, ":f:{v}"),
+            SamTagValue::String(v) => {
+                writer.write_all(b":Z:")?;
+                writer.write_all(v)
+            }
+            SamTagValue::Hex(v) => {
+                writer.write_all(b":H:")?;
+                writer.write_all(v)
+            }
+            SamTagValue::Array(v) => {
+                writer.write_all(b":B:")?;
+                writer.write_all(v)
+            }
+        }
+    }
+}
+
+/// Iterator over SAM tags in a header comment.
+///
+/// Tokens are separated by tabs or spaces, so `Z` values containing spaces
+/// are not supported. Tokens that are not SAM tags are skipped.
+#[derive(Debug, Clone)]
+pub struct SamTags<'a> {
+    tokens: std::slice::Split<'a, u8, fn(&u8) -> bool>,
+}
+
+fn is_separator(b: &u8) -> bool {
+    *b == b'\t' || *b == b' '
+}
+
+/// Returns an iterator over the SAM tags of `description`.
+pub fn sam_tags(description: &[u8]) -> SamTags<'_> {
+    SamTags {
+        tokens: description.split(is_separator as fn(&u8) -> bool),
+    }
+}
+
+impl<'a> Iterator for SamTags<'a> {
+    type Item = SamTag<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokens.by_ref().find_map(SamTag::parse)
+    }
+}
+
+impl Record {
+    /// SAM tags in the header comment.
+    pub fn sam_tags(&self) -> SamTags<'_> {
+        sam_tags(self.description().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        let tags: Vec<_> =
+            sam_tags(b"CB:Z:ACGT-1\tUB:Z:TTGG\tNM:i:-2 XA:A:x\tjunk\tRG:f:0.5").collect();
+
+        assert_eq!(tags.len(), 5);
+        assert_eq!(tags[0], SamTag::string(*b"CB", b"ACGT-1"));
+        assert_eq!(tags[1].value, SamTagValue::String(b"TTGG"));
+        assert_eq!(tags[2].value, SamTagValue::Int(-2));
+        assert_eq!(tags[3].value, SamTagValue::Char(b'x'));
+        assert_eq!(tags[4].value, SamTagValue::Float(0.5));
+    }
+
+    #[test]
+    fn test_invalid_tags() {
+        assert!(SamTag::parse(b"CB:Q:x").is_none());
+        assert!(SamTag::parse(b"1B:Z:x").is_none());
+        assert!(SamTag::parse(b"NM:i:abc").is_none());
+        assert!(SamTag::parse(b"CB:Z").is_none());
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        for token in [
+            &b"CB:Z:ACGT"[..],
+            b"NM:i:3",
+            b"XA:A:y",
+            b"BC:B:c,1,2",
+            b"XH:H:1AE3",
+        ] {
+            let mut out = Vec::new();
+            SamTag::parse(token).unwrap().write_to(&mut out).unwrap();
+            assert_eq!(&out, token);
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::kmer::{RollingKmer, hash_kmer};
+use crate::util::{parse_num, split_name};
+
+/// How a [`Sketch`] selects k-mer hashes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SketchMode {
+    /// Keep the `size` smallest hashes (bottom-k MinHash).
+    MinHash { size: usize },
+    /// Keep hashes below `u64::MAX / scaled` (FracMinHash).
+    FracMinHash { scaled: u64 },
+}
+
+/// Set of canonical k-mer hashes summarising a sequence collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sketch {
+    pub name: Vec<u8>,
+    k: usize,
+    mode: SketchMode,
+    hashes: BTreeSet<u64>,
+}
+
+impl Sketch {
+    /// Creates an empty, unnamed sketch.
+    pub fn new(k: usize, mode: SketchMode) -> Self {
+        Self {
+            name: Vec::new(),
+            k,
+            mode,
+            hashes: BTreeSet::new(),
+        }
+    }
+
+    /// The k-mer length.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// The selection mode.
+    pub fn mode(&self) -> SketchMode {
+        self.mode
+    }
+
+    /// Retained hashes in increasing order.
+    pub fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.hashes.iter().copied()
+    }
+
+    /// Number of retained hashes.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns `true` if no hash is retained.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Offers a k-mer hash to the sketch.
+    pub fn insert(&mut self, hash: u64) {
+        match self.mode {
+            SketchMode::MinHash { size } => {
+                if self.hashes.len() < size {
+                    self.hashes.insert(hash);
+                } else if self.hashes.last().is_some_and(|&max| hash < max)
+                    && self.hashes.insert(hash)
+                {
+                    self.hashes.pop_last();
+                }
+            }
+            SketchMode::FracMinHash { scaled } => {
+                if hash <= u64::MAX / scaled.max(1) {
+                    self.hashes.insert(hash);
+                }
+            }
+        }
+    }
+
+    /// Largest hash both sketches fully represent, and the MinHash size to
+    /// compare at. `None` if the sketches are incompatible.
+    fn common_bound(&self, other: &Sketch) -> Option<(u64, usize)> {
+        if self.k != other.k {
+            return None;
+        }
+        match (self.mode, other.mode) {
+            (SketchMode::MinHash { size: a }, SketchMode::MinHash { size: b }) => {
+                Some((u64::MAX, a.min(b)))
+            }
+            (SketchMode::FracMinHash { scaled: a }, SketchMode::FracMinHash { scaled: b }) => {
+                Some((u64::MAX / a.max(b).max(1), usize::MAX))
+            }
+            _ => None,
+        }
+    }
+
+    /// Estimated Jaccard similarity of the two k-mer sets, or `None` if the
+    /// sketches differ in `k` or mode. Sketches of different size or scale
+    /// are compared at the coarser one.
+    pub fn jaccard(&self, other: &Sketch) -> Option<f64> {
+        let (bound, size) = self.common_bound(other)?;
+        let (mut shared, mut union) = (0usize, 0usize);
+        for hash in self
+            .hashes
+            .union(&other.hashes)
+            .take_while(|&&h| h <= bound)
+            .take(size)
+        {
+            union += 1;
+            if self.hashes.contains(hash) && other.hashes.contains(hash) {
+                shared += 1;
+            }
+        }
+        Some(if union > 0 {
+            shared as f64 / union as f64
+        } else {
+            0.0
+        })
+    }
+
+    /// Estimated fraction of this sketch's k-mers present in `other`, or
+    /// `None` if the sketches differ in `k` or mode. For MinHash, only the
+    /// hash range covered by both sketches is compared.
+    pub fn containment(&self, other: &Sketch) -> Option<f64> {
+        let (mut bound, _) = self.common_bound(other)?;
+        if let SketchMode::MinHash { size } = other.mode
+            && other.hashes.len() >= size
+        {
+            bound = bound.min(other.hashes.last().copied().unwrap_or(u64::MAX));
+        }
+        let (mut shared, mut total) = (0usize, 0usize);
+        for hash in self.hashes.iter().take_while(|&&h| h <= bound) {
+            total += 1;
+            if other.hashes.contains(hash) {
+                shared += 1;
+            }
+        }
+        Some(if total > 0 {
+            shared as f64 / total as f64
+        } else {
+            0.0
+        })
+    }
+
+    /// Writes the sketch as a tab-separated header line
+    /// (`sketch`, `k=`, `size=` or `scaled=`, `hashes=`, `name=`) followed by
+    /// one decimal hash per line. Sketches can be concatenated.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "sketch\tk={}\t", self.k)?;
+        match self.mode {
+            SketchMode::MinHash { size } => write!(writer, "size={size}")?,
+            SketchMode::FracMinHash { scaled } => write!(writer, "scaled={scaled}")?,
+        }
+        write!(writer, "\thashes={}\tname=", self.hashes.len())?;
+        writer.write_all(&self.name)?;
+        writer.write_all(b"\n")?;
+        for hash in &self.hashes {
+            writeln!(writer, "{hash}")?;
+        }
+        Ok(())
+    }
+
+    /// Reads the next sketch written by [`Sketch::write_to`], or `None` at
+    /// end of input.
+    pub fn read_from<R: BufRead>(reader: &mut R) -> Result<Option<Self>, ReaderError> {
+        let invalid = |message: String| ReaderError::InvalidFormat { message };
+        let mut line = Vec::new();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.strip_suffix(b"\n").unwrap_or(&line);
+
+        let mut fields = header.splitn(5, |&b| b == b'\t');
+        if fields.next() != Some(b"sketch") {
+            return Err(invalid("Missing sketch header".to_string()));
+        }
+        let (mut k, mut mode, mut count, mut name) = (None, None, None, Vec::new());
+        for field in fields {
+            match field.split_at(field.iter().position(|&b| b == b'=').unwrap_or(0)) {
+                (b"k", value) => k = parse_num(&value[1..]),
+                (b"size", value) => {
+                    mode = parse_num(&value[1..]).map(|size| SketchMode::MinHash { size })
+                }
+                (b"scaled", value) => {
+                    mode = parse_num(&value[1..]).map(|scaled| SketchMode::FracMinHash { scaled })
+                }
+                (b"hashes", value) => count = parse_num::<usize>(&value[1..]),
+                (b"name", value) => name = value[1..].to_vec(),
+                _ => {
+                    return Err(invalid(format!(
+                        "Invalid sketch header field '{}'",
+                        String::from_utf8_lossy(field)
+                    )));
+                }
+            }
+        }
+        let (Some(k), Some(mode), Some(count)) = (k, mode, count) else {
+            return Err(invalid("Incomplete sketch header".to_string()));
+        };
+
+        let mut sketch = Sketch::new(k, mode);
+        sketch.name = name;
+        for _ in 0..count {
+            line.clear();
+            reader.read_until(b'\n', &mut line)?;
+            let value = line.strip_suffix(b"\n").unwrap_or(&line);
+            let hash = parse_num(value).ok_or_else(|| {
+                invalid(format!(
+                    "Invalid sketch hash '{}'",
+                    String::from_utf8_lossy(value)
+                ))
+            })?;
+            sketch.hashes.insert(hash);
+        }
+        Ok(Some(sketch))
+    }
+}
+
+/// Builds a [`Sketch`] from `SeqChunk` events.
+///
+/// K-mers are canonical and do not span records or non-ACGT bases.
+#[derive(Debug, Clone)]
+pub struct Sketcher {
+    kmer: RollingKmer,
+    sketch: Sketch,
+}
+
+impl Sketcher {
+    /// Creates a sketcher for `k`-mers.
+    ///
+    /// Panics if `k` is 0 or greater than 32.
+    pub fn new(k: usize, mode: SketchMode) -> Self {
+        Self {
+            kmer: RollingKmer::new(k),
+            sketch: Sketch::new(k, mode),
+        }
+    }
+
+    /// Adds one event.
+    pub fn observe(&mut self, event: &Event) {
+        match *event {
+            Event::NextRecord => self.kmer.reset(),
+            Event::SeqChunk(chunk) => {
+                for &b in chunk {
+                    if self.kmer.push(b) {
+                        self.sketch.insert(hash_kmer(self.kmer.canonical(), 0));
+                    }
+                }
+            }
+            Event::IdChunk(_) | Event::QualChunk(_) => {}
+        }
+    }
+
+    /// Consumes all remaining events of `reader`.
+    pub fn observe_all<E: EventReader>(&mut self, reader: &mut E) -> Result<(), ReaderError> {
+        while let Some(event) = reader.next_event() {
+            self.observe(&event?);
+        }
+        Ok(())
+    }
+
+    /// The sketch built so far.
+    pub fn sketch(&self) -> &Sketch {
+        &self.sketch
+    }
+
+    /// Returns the sketch built so far and starts an empty one.
+    pub fn take(&mut self) -> Sketch {
+        self.kmer.reset();
+        let empty = Sketch::new(self.sketch.k, self.sketch.mode);
+        std::mem::replace(&mut self.sketch, empty)
+    }
+}
+
+/// Iterator over one sketch per record, named after the record.
+pub struct RecordSketches<E> {
+    reader: E,
+    sketcher: Sketcher,
+    name: Vec<u8>,
+    started: bool,
+}
+
+impl<E: EventReader> RecordSketches<E> {
+    /// Sketches each record of `reader`.
+    ///
+    /// Panics if `k` is 0 or greater than 32.
+    pub fn new(reader: E, k: usize, mode: SketchMode) -> Self {
+        Self {
+            reader,
+            sketcher: Sketcher::new(k, mode),
+            name: Vec::new(),
+            started: false,
+        }
+    }
+
+    fn finish_record(&mut self) -> Sketch {
+        let mut sketch = self.sketcher.take();
+        let end = split_name(&self.name).0.len();
+        self.name.truncate(end);
+        sketch.name = std::mem::take(&mut self.name);
+        sketch
+    }
+}
+
+impl<E: EventReader> Iterator for RecordSketches<E> {
+    type Item = Result<Sketch, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.reader.next_event() {
+                None => {
+                    let started = std::mem::replace(&mut self.started, false);
+                    return started.then(|| Ok(self.finish_record()));
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(Event::NextRecord)) => return Some(Ok(self.finish_record())),
+                Some(Ok(Event::IdChunk(chunk))) => {
+                    self.started = true;
+                    self.name.extend_from_slice(chunk);
+                }
+                Some(Ok(event)) => {
+                    self.started = true;
+                    self.sketcher.observe(&event);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastaReader;
+    use std::io::Cursor;
+
+    const DATA: &[u8] = b">a first\nACGTTGCAAGCTTCGAGATCACGTTGCAAGGTTCAG\n\
+                          >b\nACGTTGCAAGCTTCGAGATC\nACGTTGCAAGGTTCAG\n\
+                          >c\nGGGTACCTTAGGACATTTGACCAGTGGCCATA\n";
+
+    fn record_sketches(capacity: usize, mode: SketchMode) -> Vec<Sketch> {
+        RecordSketches::new(FastaReader::with_capacity(capacity, DATA), 7, mode)
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_minhash_keeps_smallest() {
+        let mut sketch = Sketch::new(5, SketchMode::MinHash { size: 3 });
+        for hash in [50, 10, 40, 30, 10, 20] {
+            sketch.insert(hash);
+        }
+        assert_eq!(sketch.hashes().collect::<Vec<_>>(), vec![10, 20, 30]);
+
+        let mut frac = Sketch::new(5, SketchMode::FracMinHash { scaled: 2 });
+        frac.insert(u64::MAX);
+        frac.insert(7);
+        assert_eq!(frac.len(), 1);
+        assert_eq!(sketch.jaccard(&frac), None);
+    }
+
+    #[test]
+    fn test_record_sketches_compare() {
+        for mode in [
+            SketchMode::MinHash { size: 8 },
+            SketchMode::FracMinHash { scaled: 1 },
+        ] {
+            let sketches = record_sketches(4, mode);
+            assert_eq!(sketches, record_sketches(128, mode));
+            assert_eq!(sketches.len(), 3);
+            assert_eq!(sketches[0].name, b"a");
+            assert_eq!(sketches[0].jaccard(&sketches[1]), Some(1.0));
+            assert_eq!(sketches[0].jaccard(&sketches[2]), Some(0.0));
+            assert_eq!(sketches[2].containment(&sketches[2]), Some(1.0));
+        }
+
+        let mut sketcher = Sketcher::new(7, SketchMode::FracMinHash { scaled: 1 });
+        sketcher.observe_all(&mut FastaReader::new(DATA)).unwrap();
+        let file = sketcher.take();
+        let sketches = record_sketches(64, SketchMode::FracMinHash { scaled: 1 });
+        assert_eq!(sketches[0].containment(&file), Some(1.0));
+        let jaccard = sketches[2].jaccard(&file).unwrap();
+        assert!(jaccard > 0.4 && jaccard < 0.6);
+    }
+
+    #[test]
+    fn test_serialisation_round_trip() {
+        let sketches = record_sketches(64, SketchMode::MinHash { size: 4 });
+        let mut out = Vec::new();
+        for sketch in &sketches {
+            sketch.write_to(&mut out).unwrap();
+        }
+        assert!(out.starts_with(b"sketch\tk=7\tsize=4\thashes=4\tname=a\n"));
+
+        let mut reader = Cursor::new(out);
+        let mut read = Vec::new();
+        while let Some(sketch) = Sketch::read_from(&mut reader).unwrap() {
+            read.push(sketch);
+        }
+        assert_eq!(read, sketches);
+
+        assert!(Sketch::read_from(&mut &b"sketch\tk=7\thashes=1\tname=\n1\n"[..]).is_err());
+        assert!(Sketch::read_from(&mut &b"sketch\tk=7\tsize=2\thashes=1\tname=\nx\n"[..]).is_err());
+    }
+}
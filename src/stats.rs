@@ -0,0 +1,228 @@
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::quality::QualityEncoding;
+
+/// Summary computed by [`Stats`], in the spirit of `seqkit stats -a`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsSummary {
+    pub records: u64,
+    pub total_length: u64,
+    pub min_length: u64,
+    pub max_length: u64,
+    pub mean_length: f64,
+    pub n50: u64,
+    pub n90: u64,
+    /// `G + C` over `A + C + G + T`, case-insensitive.
+    pub gc_content: f64,
+    pub n_count: u64,
+    /// Fraction of quality scores at least Q20 (`None` for FASTA).
+    pub q20_fraction: Option<f64>,
+    /// Fraction of quality scores at least Q30 (`None` for FASTA).
+    pub q30_fraction: Option<f64>,
+    /// Mean quality at each read position (empty for FASTA).
+    pub position_quality: Vec<f64>,
+}
+
+/// Sequence statistics accumulated directly from events, without
+/// assembling records. Record lengths are kept for N50/N90.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    offset: u8,
+    lengths: Vec<u64>,
+    current_len: u64,
+    current_qual_pos: usize,
+    open: bool,
+    gc: u64,
+    at: u64,
+    n: u64,
+    qual_count: u64,
+    q20: u64,
+    q30: u64,
+    position_sums: Vec<(u64, u64)>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Stats {
+    /// Creates empty statistics for Phred+33 qualities.
+    pub fn new() -> Self {
+        Self {
+            offset: QualityEncoding::Phred33.offset(),
+            lengths: Vec::new(),
+            current_len: 0,
+            current_qual_pos: 0,
+            open: false,
+            gc: 0,
+            at: 0,
+            n: 0,
+            qual_count: 0,
+            q20: 0,
+            q30: 0,
+            position_sums: Vec::new(),
+        }
+    }
+
+    /// Sets the ASCII offset of quality characters.
+    pub fn with_offset(mut self, offset: u8) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Adds one event.
+    pub fn observe(&mut self, event: &Event) {
+        match *event {
+            Event::NextRecord => {
+                self.lengths.push(self.current_len);
+                self.current_len = 0;
+                self.current_qual_pos = 0;
+            }
+            Event::IdChunk(_) => {}
+            Event::SeqChunk(chunk) => {
+                self.current_len += chunk.len() as u64;
+                for &b in chunk {
+                    match b {
+                        b'G' | b'C' | b'g' | b'c' => self.gc += 1,
+                        b'A' | b'T' | b'a' | b't' => self.at += 1,
+                        b'N' | b'n' => self.n += 1,
+                        _ => {}
+                    }
+                }
+            }
+            Event::QualChunk(chunk) => {
+                let end = self.current_qual_pos + chunk.len();
+                if self.position_sums.len() < end {
+                    self.position_sums.resize(end, (0, 0));
+                }
+                for (&b, slot) in chunk
+                    .iter()
+                    .zip(&mut self.position_sums[self.current_qual_pos..end])
+                {
+                    let q = b.saturating_sub(self.offset) as u64;
+                    slot.0 += q;
+                    slot.1 += 1;
+                    if q >= 20 {
+                        self.q20 += 1;
+                    }
+                    if q >= 30 {
+                        self.q30 += 1;
+                    }
+                }
+                self.current_qual_pos = end;
+                self.qual_count += chunk.len() as u64;
+            }
+        }
+        self.open = true;
+    }
+
+    /// Consumes all remaining events of `reader`.
+    pub fn observe_all<E: EventReader>(&mut self, reader: &mut E) -> Result<(), ReaderError> {
+        while let Some(event) = reader.next_event() {
+            self.observe(&event?);
+        }
+        Ok(())
+    }
+
+    /// Computes the summary of everything observed so far.
+    pub fn summary(&self) -> StatsSummary {
+        let mut lengths = self.lengths.clone();
+        if self.open {
+            lengths.push(self.current_len);
+        }
+        if lengths.is_empty() {
+            return StatsSummary::default();
+        }
+        lengths.sort_unstable_by(|a, b| b.cmp(a));
+
+        let total: u64 = lengths.iter().sum();
+        let nx = |fraction: f64| {
+            let target = (total as f64 * fraction).ceil() as u64;
+            let mut acc = 0;
+            for &len in &lengths {
+                acc += len;
+                if acc >= target {
+                    return len;
+                }
+            }
+            0
+        };
+        let fraction =
+            |count: u64| (self.qual_count > 0).then(|| count as f64 / self.qual_count as f64);
+        let acgt = self.gc + self.at;
+
+        StatsSummary {
+            records: lengths.len() as u64,
+            total_length: total,
+            min_length: *lengths.last().unwrap(),
+            max_length: lengths[0],
+            mean_length: total as f64 / lengths.len() as f64,
+            n50: nx(0.5),
+            n90: nx(0.9),
+            gc_content: if acgt > 0 {
+                self.gc as f64 / acgt as f64
+            } else {
+                0.0
+            },
+            n_count: self.n,
+            q20_fraction: fraction(self.q20),
+            q30_fraction: fraction(self.q30),
+            position_quality: self
+                .position_sums
+                .iter()
+                .map(|&(sum, count)| sum as f64 / count as f64)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FastaReader, FastqReader};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_fasta_stats() {
+        let data = b">a\nACGTAC\nGG\n>b\nNNAT\n>c\nGC\n>d\nAAAAAAAAAA\n";
+        let mut stats = Stats::new();
+        stats
+            .observe_all(&mut FastaReader::with_capacity(3, Cursor::new(&data[..])))
+            .unwrap();
+        let summary = stats.summary();
+
+        assert_eq!(summary.records, 4);
+        assert_eq!(summary.total_length, 24);
+        assert_eq!(summary.min_length, 2);
+        assert_eq!(summary.max_length, 10);
+        assert_eq!(summary.mean_length, 6.0);
+        assert_eq!(summary.n50, 8);
+        assert_eq!(summary.n90, 4);
+        assert_eq!(summary.n_count, 2);
+        assert!((summary.gc_content - 7.0 / 22.0).abs() < 1e-12);
+        assert_eq!(summary.q30_fraction, None);
+        assert!(summary.position_quality.is_empty());
+    }
+
+    #[test]
+    fn test_fastq_stats() {
+        let data = b"@r1\nACGT\n+\nI5I5\n@r2\nAC\n+\n+I\n";
+        let mut stats = Stats::new();
+        stats
+            .observe_all(&mut FastqReader::with_capacity(4, Cursor::new(&data[..])))
+            .unwrap();
+        let summary = stats.summary();
+
+        assert_eq!(summary.records, 2);
+        assert_eq!(summary.q20_fraction, Some(5.0 / 6.0));
+        assert_eq!(summary.q30_fraction, Some(3.0 / 6.0));
+        assert_eq!(summary.position_quality, vec![25.0, 30.0, 40.0, 20.0]);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(Stats::new().summary(), StatsSummary::default());
+    }
+}
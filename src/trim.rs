@@ -0,0 +1,241 @@
+use crate::error::ReaderError;
+use crate::event::EventReader;
+use crate::quality::QualityEncoding;
+use crate::record::{Record, RecordReader};
+
+/// Quality-based 3' read trimmer.
+///
+/// Steps run in order: sliding window, Mott/BWA cutoff, trailing `N` removal.
+/// Quality steps are skipped for records without quality strings.
+#[derive(Debug, Clone)]
+pub struct QualityTrimmer {
+    offset: u8,
+    window: Option<(usize, u8)>,
+    cutoff: Option<u8>,
+    trim_trailing_n: bool,
+}
+
+impl Default for QualityTrimmer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QualityTrimmer {
+    /// Creates a trimmer for Phred+33 data with no steps enabled.
+    pub fn new() -> Self {
+        Self {
+            offset: QualityEncoding::Phred33.offset(),
+            window: None,
+            cutoff: None,
+            trim_trailing_n: false,
+        }
+    }
+
+    /// Sets the ASCII offset of quality characters.
+    pub fn with_offset(mut self, offset: u8) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Cuts at the first `size`-base window whose mean quality is below
+    /// `threshold` (Trimmomatic `SLIDINGWINDOW`).
+    pub fn with_sliding_window(mut self, size: usize, threshold: u8) -> Self {
+        self.window = Some((size.max(1), threshold));
+        self
+    }
+
+    /// Trims the 3' end with the Mott/BWA algorithm (cutadapt `-q`).
+    pub fn with_quality_cutoff(mut self, cutoff: u8) -> Self {
+        self.cutoff = Some(cutoff);
+        self
+    }
+
+    /// Removes trailing `N` bases.
+    pub fn with_trailing_n(mut self, enabled: bool) -> Self {
+        self.trim_trailing_n = enabled;
+        self
+    }
+
+    /// Trims `record` in place. Returns the number of bases removed.
+    pub fn trim(&self, record: &mut Record) -> usize {
+        let original = record.seq.len();
+        let mut len = original;
+
+        if record.qual.len() == original {
+            if let Some((size, threshold)) = self.window {
+                len = self.sliding_window(&record.qual[..len], size, threshold);
+            }
+            if let Some(cutoff) = self.cutoff {
+                len = self.mott(&record.qual[..len], cutoff);
+            }
+        }
+
+        if self.trim_trailing_n {
+            len = record.seq[..len]
+                .iter()
+                .rposition(|&b| b != b'N' && b != b'n')
+                .map_or(0, |pos| pos + 1);
+        }
+
+        record.seq.truncate(len);
+        record.qual.truncate(len);
+        original - len
+    }
+
+    fn sliding_window(&self, qual: &[u8], size: usize, threshold: u8) -> usize {
+        if qual.len() < size {
+            let sum: usize = qual.iter().map(|&b| b.saturating_sub(self.offset) as usize).sum();
+            return if sum < threshold as usize * qual.len() { 0 } else { qual.len() };
+        }
+
+        let required = threshold as usize * size;
+        let mut sum: usize = qual[..size]
+            .iter()
+            .map(|&b| b.saturating_sub(self.offset) as usize)
+            .sum();
+
+        for start in 0..=qual.len() - size {
+            if start > 0 {
+                sum -= qual[start - 1].saturating_sub(self.offset) as usize;
+                sum += qual[start + size - 1].saturating_sub(self.offset) as usize;
+            }
+            if sum < required {
+                return start;
+            }
+        }
+        qual.len()
+    }
+
+    fn mott(&self, qual: &[u8], cutoff: u8) -> usize {
+        let mut sum: i64 = 0;
+        let mut max: i64 = 0;
+        let mut cut = qual.len();
+
+        for (i, &b) in qual.iter().enumerate().rev() {
+            sum += cutoff as i64 - b.saturating_sub(self.offset) as i64;
+            if sum < 0 {
+                break;
+            }
+            if sum > max {
+                max = sum;
+                cut = i;
+            }
+        }
+        cut
+    }
+}
+
+/// Streams trimmed records from an event reader such as `FastqReader`.
+pub struct TrimmingReader<E> {
+    reader: RecordReader<E>,
+    trimmer: QualityTrimmer,
+    record: Record,
+    trimmed_bases: u64,
+}
+
+impl<E: EventReader> TrimmingReader<E> {
+    /// Wraps `reader`, trimming each record with `trimmer`.
+    pub fn new(reader: E, trimmer: QualityTrimmer) -> Self {
+        Self {
+            reader: RecordReader::new(reader),
+            trimmer,
+            record: Record::new(),
+            trimmed_bases: 0,
+        }
+    }
+
+    /// Reads and trims the next record into `record`, replacing its contents.
+    /// Returns `false` at EOF.
+    pub fn read_record(&mut self, record: &mut Record) -> Result<bool, ReaderError> {
+        if !self.reader.read_record(record)? {
+            return Ok(false);
+        }
+        self.trimmed_bases += self.trimmer.trim(record) as u64;
+        Ok(true)
+    }
+
+    /// Returns the next trimmed record, or `None` at EOF. The record's
+    /// buffers are reused by the next call.
+    pub fn next_record(&mut self) -> Result<Option<&Record>, ReaderError> {
+        let mut record = std::mem::take(&mut self.record);
+        let found = self.read_record(&mut record);
+        self.record = record;
+        Ok(found?.then_some(&self.record))
+    }
+
+    /// Total bases removed so far.
+    pub fn trimmed_bases(&self) -> u64 {
+        self.trimmed_bases
+    }
+
+    /// Unwraps the underlying event reader.
+    pub fn into_inner(self) -> E {
+        self.reader.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FastqReader;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_mott_trimming() {
+        // Q: 40 40 40 40 10 10 30 10 2
+        let mut r = Record::from_parts(b"read", b"ACGTACGTA", b"IIII++?+#");
+        let removed = QualityTrimmer::new().with_quality_cutoff(20).trim(&mut r);
+
+        assert_eq!(removed, 5);
+        assert_eq!(&r.seq, b"ACGT");
+        assert_eq!(&r.qual, b"IIII");
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        // Q: 40 40 40 40 10 10 40 40
+        let mut r = Record::from_parts(b"read", b"ACGTACGT", b"IIII++II");
+        QualityTrimmer::new().with_sliding_window(2, 20).trim(&mut r);
+
+        assert_eq!(&r.seq, b"ACGT");
+        assert_eq!(&r.qual, b"IIII");
+    }
+
+    #[test]
+    fn test_trailing_n() {
+        let mut r = Record::from_parts(b"read", b"ACGTNnN", b"IIIIIII");
+        let removed = QualityTrimmer::new().with_trailing_n(true).trim(&mut r);
+
+        assert_eq!(removed, 3);
+        assert_eq!(&r.seq, b"ACGT");
+        assert_eq!(&r.qual, b"IIII");
+
+        let mut fasta = Record::from_parts(b"read", b"NNNN", b"");
+        QualityTrimmer::new().with_trailing_n(true).trim(&mut fasta);
+        assert!(fasta.seq.is_empty());
+    }
+
+    #[test]
+    fn test_trimming_reader() {
+        let data = b"@r1 first\nACGTNN\n+\nIIIIII\n\
+                     @r2\nACGTACGTA\n+\nIIII++?+#\n\
+                     @r3\nNNN\n+\nIII\n";
+        let trimmer = QualityTrimmer::new().with_quality_cutoff(20).with_trailing_n(true);
+        let fastq = FastqReader::with_capacity(4, Cursor::new(&data[..]));
+        let mut reader = TrimmingReader::new(fastq, trimmer);
+
+        let r1 = reader.next_record().unwrap().unwrap();
+        assert_eq!(&r1.id, b"r1 first");
+        assert_eq!((&r1.seq[..], &r1.qual[..]), (&b"ACGT"[..], &b"IIII"[..]));
+        let r2 = reader.next_record().unwrap().unwrap();
+        assert_eq!(&r2.id, b"r2");
+        assert_eq!((&r2.seq[..], &r2.qual[..]), (&b"ACGT"[..], &b"IIII"[..]));
+
+        let mut record = Record::new();
+        assert!(reader.read_record(&mut record).unwrap());
+        assert!(record.seq.is_empty() && record.qual.is_empty());
+        assert!(reader.next_record().unwrap().is_none());
+        assert_eq!(reader.trimmed_bases(), 2 + 5 + 3);
+    }
+}
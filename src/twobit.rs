@@ -0,0 +1,673 @@
+use std::collections::HashSet;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+use crate::error::ReaderError;
+use crate::event::{Event, EventReader};
+use crate::packed::{PackedRecord, PackedSequence};
+use crate::util::split_name;
+
+/// Signature at the start of every .2bit file, in the writer's byte order.
+const SIGNATURE: u32 = 0x1A41_2743;
+
+/// Bases decoded per `SeqChunk` event.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Id,
+    Seq,
+    Done,
+}
+
+/// Reader for UCSC .2bit files.
+///
+/// Emits the same events as [`FastaReader`](crate::FastaReader) on the
+/// equivalent FASTA: the record name as `IdChunk`, bases (lowercase where
+/// soft-masked, `N` in N blocks) as `SeqChunk`s and `NextRecord` between
+/// records. Chunk boundaries are not line boundaries. Both byte orders and
+/// versions 0 (32-bit offsets) and 1 (64-bit offsets) are supported.
+///
+/// Streaming holds one packed record in memory; [`TwoBitReader::fetch`]
+/// reads only the bytes of the requested region.
+pub struct TwoBitReader<R> {
+    reader: R,
+    big_endian: bool,
+    index: Vec<(Vec<u8>, u64)>,
+    file_len: u64,
+    current: usize,
+    stage: Stage,
+    seq: PackedSequence,
+    pos: usize,
+    chunk: Vec<u8>,
+}
+
+impl<R: Read + Seek> TwoBitReader<R> {
+    /// Reads the header and sequence index.
+    pub fn new(mut reader: R) -> Result<Self, ReaderError> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+        let mut word = [0; 4];
+        reader.read_exact(&mut word)?;
+        let big_endian = if u32::from_le_bytes(word) == SIGNATURE {
+            false
+        } else if u32::from_be_bytes(word) == SIGNATURE {
+            true
+        } else {
+            return Err(ReaderError::InvalidFormat {
+                message: "Invalid .2bit signature".to_string(),
+            });
+        };
+
+        let mut this = Self {
+            reader,
+            big_endian,
+            index: Vec::new(),
+            file_len,
+            current: 0,
+            stage: Stage::Id,
+            seq: PackedSequence::new(),
+            pos: 0,
+            chunk: Vec::new(),
+        };
+
+        let version = this.read_u32()?;
+        if version > 1 {
+            return Err(ReaderError::InvalidFormat {
+                message: format!("Unsupported .2bit version {version}"),
+            });
+        }
+        let count = this.read_u32()?;
+        this.read_u32()?; // reserved
+        this.check_remaining(count as u64 * 5, "sequence index")?;
+
+        for _ in 0..count {
+            let mut len = [0; 1];
+            this.reader.read_exact(&mut len)?;
+            let mut name = vec![0; len[0] as usize];
+            this.reader.read_exact(&mut name)?;
+            let offset = if version == 1 {
+                this.read_u64()?
+            } else {
+                this.read_u32()? as u64
+            };
+            this.index.push((name, offset));
+        }
+        Ok(this)
+    }
+
+    fn read_u32(&mut self) -> io::Result<u32> {
+        let mut bytes = [0; 4];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn read_u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        self.reader.read_exact(&mut bytes)?;
+        Ok(if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        })
+    }
+
+    /// Fails unless `bytes` more bytes follow the current position, so that
+    /// corrupt counts and lengths are rejected before allocating for them.
+    fn check_remaining(&mut self, bytes: u64, what: &str) -> Result<(), ReaderError> {
+        let pos = self.reader.stream_position()?;
+        if bytes > self.file_len.saturating_sub(pos) {
+            return Err(ReaderError::InvalidFormat {
+                message: format!("Truncated .2bit {what} at offset {pos}"),
+            });
+        }
+        Ok(())
+    }
+
+    fn read_blocks(&mut self) -> Result<Vec<Range<usize>>, ReaderError> {
+        let count = self.read_u32()? as usize;
+        self.check_remaining(count as u64 * 8, "block list")?;
+        let starts = (0..count)
+            .map(|_| self.read_u32())
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut blocks = Vec::with_capacity(count);
+        for start in starts {
+            let start = start as usize;
+            blocks.push(start..start + self.read_u32()? as usize);
+        }
+        blocks.sort_unstable_by_key(|block| block.start);
+        Ok(blocks)
+    }
+
+    /// Sequence names in file order.
+    pub fn names(&self) -> impl Iterator<Item = &[u8]> {
+        self.index.iter().map(|(name, _)| name.as_slice())
+    }
+
+    fn find(&self, name: &[u8]) -> Result<usize, ReaderError> {
+        self.index
+            .iter()
+            .position(|(n, _)| n == name)
+            .ok_or_else(|| ReaderError::InvalidFormat {
+                message: format!("Unknown .2bit sequence '{}'", String::from_utf8_lossy(name)),
+            })
+    }
+
+    /// Length of sequence `name`.
+    pub fn sequence_len(&mut self, name: &[u8]) -> Result<usize, ReaderError> {
+        let index = self.find(name)?;
+        self.reader.seek(SeekFrom::Start(self.index[index].1))?;
+        Ok(self.read_u32()? as usize)
+    }
+
+    /// Reads `range` of record `index` as a packed sequence starting at the
+    /// byte containing `range.start`. Returns it with the offset of
+    /// `range.start` within it.
+    fn read_region(
+        &mut self,
+        index: usize,
+        range: Option<Range<usize>>,
+    ) -> Result<(PackedSequence, Range<usize>), ReaderError> {
+        self.reader.seek(SeekFrom::Start(self.index[index].1))?;
+        let len = self.read_u32()? as usize;
+        let n_blocks = self.read_blocks()?;
+        let mask_blocks = self.read_blocks()?;
+        self.read_u32()?; // reserved
+        self.check_remaining(len.div_ceil(4) as u64, "sequence")?;
+
+        let range = range.unwrap_or(0..len);
+        if range.start > range.end || range.end > len {
+            return Err(ReaderError::InvalidFormat {
+                message: format!(
+                    "Region {}..{} out of bounds for .2bit sequence '{}' of length {len}",
+                    range.start,
+                    range.end,
+                    String::from_utf8_lossy(&self.index[index].0)
+                ),
+            });
+        }
+
+        let shift = range.start / 4 * 4;
+        let mut packed = vec![0; range.end.div_ceil(4) - shift / 4];
+        self.reader.seek(SeekFrom::Current((shift / 4) as i64))?;
+        self.reader.read_exact(&mut packed)?;
+
+        let clip = |blocks: Vec<Range<usize>>| {
+            blocks
+                .into_iter()
+                .map(|b| b.start.max(shift) - shift..b.end.min(range.end).max(shift) - shift)
+                .filter(|b| !b.is_empty())
+                .collect()
+        };
+        let seq = PackedSequence::from_parts(
+            range.end - shift,
+            packed,
+            clip(n_blocks),
+            clip(mask_blocks),
+        )?;
+        Ok((seq, range.start - shift..range.end - shift))
+    }
+
+    /// Appends bases `range` of sequence `name` to `out`, reading only that
+    /// region from the file.
+    pub fn fetch(
+        &mut self,
+        name: &[u8],
+        range: Range<usize>,
+        out: &mut Vec<u8>,
+    ) -> Result<(), ReaderError> {
+        let index = self.find(name)?;
+        let (seq, local) = self.read_region(index, Some(range))?;
+        seq.decode_range(local, out);
+        Ok(())
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Returns the next event, or `None` at EOF.
+    pub fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>> {
+        match self.stage {
+            Stage::Id => {
+                let index = self.current;
+                if index >= self.index.len() {
+                    self.stage = Stage::Done;
+                    return None;
+                }
+                match self.read_region(index, None) {
+                    Ok((seq, _)) => self.seq = seq,
+                    Err(e) => {
+                        self.stage = Stage::Done;
+                        return Some(Err(e));
+                    }
+                }
+                self.pos = 0;
+                self.stage = Stage::Seq;
+                Some(Ok(Event::IdChunk(&self.index[index].0)))
+            }
+            Stage::Seq => {
+                if self.pos < self.seq.len() {
+                    let end = (self.pos + CHUNK_SIZE).min(self.seq.len());
+                    self.chunk.clear();
+                    self.seq.decode_range(self.pos..end, &mut self.chunk);
+                    self.pos = end;
+                    return Some(Ok(Event::SeqChunk(&self.chunk)));
+                }
+                self.current += 1;
+                if self.current >= self.index.len() {
+                    self.stage = Stage::Done;
+                    return None;
+                }
+                self.stage = Stage::Id;
+                Some(Ok(Event::NextRecord))
+            }
+            Stage::Done => None,
+        }
+    }
+}
+
+impl<R: Read + Seek> EventReader for TwoBitReader<R> {
+    fn next_event(&mut self) -> Option<Result<Event<'_>, ReaderError>> {
+        TwoBitReader::next_event(self)
+    }
+}
+
+/// Bytes moved per read/write when [`TwoBitWriter::finish`] makes room for
+/// the index.
+const SHIFT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Writer of UCSC .2bit files from FASTA events.
+///
+/// Records are packed as their events stream in, with N and lowercase runs
+/// recorded as blocks, and each is written out as soon as it ends, so only
+/// one packed record is held in memory. The index at the start of the file
+/// needs every name, so [`TwoBitWriter::finish`] moves the written records
+/// forward to make room for it and back-patches the header and offsets;
+/// this is why the output must also be readable and seekable. Records are
+/// named by the first word of their header.
+pub struct TwoBitWriter<W: Read + Write + Seek> {
+    writer: BufWriter<W>,
+    /// Name and offset of each written record, relative to the first.
+    index: Vec<(Vec<u8>, u64)>,
+    names: HashSet<Vec<u8>>,
+    body_len: u64,
+    current: PackedRecord,
+    started: bool,
+}
+
+impl<W: Read + Write + Seek> TwoBitWriter<W> {
+    /// Creates a writer. The file starts at the current position of `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(128 * 1024, writer),
+            index: Vec::new(),
+            names: HashSet::new(),
+            body_len: 0,
+            current: PackedRecord::default(),
+            started: false,
+        }
+    }
+
+    /// Adds one event.
+    pub fn observe(&mut self, event: &Event) -> Result<(), ReaderError> {
+        match *event {
+            Event::NextRecord => return self.end_record(),
+            Event::IdChunk(chunk) => self.current.id.extend_from_slice(chunk),
+            Event::SeqChunk(chunk) => self.current.seq.push(chunk),
+            Event::QualChunk(_) => {}
+        }
+        self.started = true;
+        Ok(())
+    }
+
+    /// Consumes all remaining events of `reader`.
+    pub fn write_all<E: EventReader>(&mut self, reader: &mut E) -> Result<(), ReaderError> {
+        while let Some(event) = reader.next_event() {
+            self.observe(&event?)?;
+        }
+        Ok(())
+    }
+
+    /// Adds an already packed record.
+    pub fn write_packed(&mut self, record: PackedRecord) -> Result<(), ReaderError> {
+        self.end_record()?;
+        self.current = record;
+        self.started = true;
+        self.end_record()
+    }
+
+    fn end_record(&mut self) -> Result<(), ReaderError> {
+        if !std::mem::take(&mut self.started) {
+            return Ok(());
+        }
+        let name = split_name(&self.current.id).0.to_vec();
+        let seq = std::mem::take(&mut self.current.seq);
+        self.current.id.clear();
+
+        if name.len() > u8::MAX as usize {
+            return Err(ReaderError::InvalidFormat {
+                message: format!(
+                    "Sequence name '{}' is longer than 255 bytes",
+                    String::from_utf8_lossy(&name)
+                ),
+            });
+        }
+        if !self.names.insert(name.clone()) {
+            return Err(ReaderError::InvalidFormat {
+                message: format!(
+                    "Duplicate sequence name '{}'",
+                    String::from_utf8_lossy(&name)
+                ),
+            });
+        }
+        if seq.len() > u32::MAX as usize {
+            return Err(ReaderError::InvalidFormat {
+                message: format!(
+                    "Sequence '{}' is too long for .2bit",
+                    String::from_utf8_lossy(&name)
+                ),
+            });
+        }
+
+        self.write_u32(seq.len() as u32)?;
+        self.write_blocks(seq.n_runs())?;
+        self.write_blocks(seq.mask_runs())?;
+        self.write_u32(0)?; // reserved
+        self.writer.write_all(seq.packed())?;
+
+        self.index.push((name, self.body_len));
+        self.body_len += 16
+            + 8 * (seq.n_runs().len() + seq.mask_runs().len()) as u64
+            + seq.packed().len() as u64;
+        self.current.seq = seq;
+        self.current.seq.clear();
+        Ok(())
+    }
+
+    fn write_u32(&mut self, value: u32) -> io::Result<()> {
+        self.writer.write_all(&value.to_le_bytes())
+    }
+
+    fn write_blocks(&mut self, blocks: &[Range<usize>]) -> io::Result<()> {
+        self.write_u32(blocks.len() as u32)?;
+        for block in blocks {
+            self.write_u32(block.start as u32)?;
+        }
+        for block in blocks {
+            self.write_u32(block.len() as u32)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the header and index, little-endian, in front of the records
+    /// and returns the underlying writer, positioned at the end of the file.
+    /// Version 1 (64-bit offsets) is used only if the file exceeds 4 GiB.
+    pub fn finish(mut self) -> Result<W, ReaderError> {
+        self.end_record()?;
+
+        let names_len: u64 = self
+            .index
+            .iter()
+            .map(|(name, _)| 1 + name.len() as u64)
+            .sum();
+        let count = self.index.len() as u64;
+        let version = if 16 + names_len + 4 * count + self.body_len > u32::MAX as u64 {
+            1
+        } else {
+            0
+        };
+        let offset_size = if version == 1 { 8 } else { 4 };
+        let header_len = 16 + names_len + offset_size * count;
+
+        let end = self.writer.stream_position()?;
+        let start = end - self.body_len;
+        self.shift_records(start, header_len)?;
+
+        self.writer.seek(SeekFrom::Start(start))?;
+        for word in [SIGNATURE, version, count as u32, 0] {
+            self.write_u32(word)?;
+        }
+        let index = std::mem::take(&mut self.index);
+        for (name, offset) in &index {
+            let offset = header_len + offset;
+            self.writer.write_all(&[name.len() as u8])?;
+            self.writer.write_all(name)?;
+            if version == 1 {
+                self.writer.write_all(&offset.to_le_bytes())?;
+            } else {
+                self.write_u32(offset as u32)?;
+            }
+        }
+        self.writer.seek(SeekFrom::Start(end + header_len))?;
+
+        Ok(self.writer.into_inner().map_err(|e| e.into_error())?)
+    }
+
+    /// Moves the written records, starting at `start`, forward by `by`
+    /// bytes, last chunk first.
+    fn shift_records(&mut self, start: u64, by: u64) -> io::Result<()> {
+        self.writer.flush()?;
+        let writer = self.writer.get_mut();
+        let mut buf = vec![0; SHIFT_CHUNK_SIZE.min(self.body_len as usize)];
+        let mut remaining = self.body_len;
+        while remaining > 0 {
+            let len = remaining.min(buf.len() as u64);
+            let pos = start + remaining - len;
+            let chunk = &mut buf[..len as usize];
+            writer.seek(SeekFrom::Start(pos))?;
+            writer.read_exact(chunk)?;
+            writer.seek(SeekFrom::Start(pos + by))?;
+            writer.write_all(chunk)?;
+            remaining -= len;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FastaReader, Record, RecordReader};
+    use std::io::Cursor;
+
+    const FASTA: &[u8] = b">chr1 first\nACGTNNNNacgtTTGCAnnA\nGGCATT\n>chr2\n\n>chr3\ntttt\n";
+
+    /// Encodes `FASTA` as .2bit by hand, in either byte order and version.
+    fn two_bit(big_endian: bool, version: u32) -> Vec<u8> {
+        let u32_bytes = |v: u32| {
+            if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            }
+        };
+        let records = [
+            ("chr1", &b"ACGTNNNNacgtTTGCAnnAGGCATT"[..]),
+            ("chr2", b""),
+            ("chr3", b"tttt"),
+        ];
+
+        let index_len: usize = records
+            .iter()
+            .map(|(name, _)| 1 + name.len() + if version == 1 { 8 } else { 4 })
+            .sum();
+        let mut body = Vec::new();
+        let mut index = Vec::new();
+        for (name, seq) in records {
+            let offset = (16 + index_len + body.len()) as u64;
+            index.push(name.len() as u8);
+            index.extend_from_slice(name.as_bytes());
+            if version == 1 {
+                index.extend(if big_endian {
+                    offset.to_be_bytes()
+                } else {
+                    offset.to_le_bytes()
+                });
+            } else {
+                index.extend(u32_bytes(offset as u32));
+            }
+
+            let packed = PackedSequence::from_bytes(seq);
+            body.extend(u32_bytes(seq.len() as u32));
+            for runs in [packed.n_runs(), packed.mask_runs()] {
+                body.extend(u32_bytes(runs.len() as u32));
+                for run in runs {
+                    body.extend(u32_bytes(run.start as u32));
+                }
+                for run in runs {
+                    body.extend(u32_bytes(run.len() as u32));
+                }
+            }
+            body.extend(u32_bytes(0));
+            body.extend_from_slice(packed.packed());
+        }
+
+        let mut file = Vec::new();
+        for word in [SIGNATURE, version, records.len() as u32, 0] {
+            file.extend(u32_bytes(word));
+        }
+        file.extend(index);
+        file.extend(body);
+        file
+    }
+
+    fn records<E: EventReader>(reader: E) -> Vec<Record> {
+        let mut reader = RecordReader::new(reader);
+        let mut records = Vec::new();
+        let mut record = Record::new();
+        while reader.read_record(&mut record).unwrap() {
+            records.push(record.clone());
+        }
+        records
+    }
+
+    #[test]
+    fn test_events_match_fasta() {
+        let mut expected = records(FastaReader::new(FASTA));
+        expected[0].id.truncate(4);
+
+        for (big_endian, version) in [(false, 0), (true, 0), (false, 1), (true, 1)] {
+            let reader = TwoBitReader::new(Cursor::new(two_bit(big_endian, version))).unwrap();
+            assert_eq!(
+                reader.names().collect::<Vec<_>>(),
+                [b"chr1", b"chr2", b"chr3"]
+            );
+            assert_eq!(records(reader), expected);
+        }
+    }
+
+    #[test]
+    fn test_fetch_region() {
+        let mut reader = TwoBitReader::new(Cursor::new(two_bit(false, 0))).unwrap();
+        assert_eq!(reader.sequence_len(b"chr1").unwrap(), 26);
+
+        let mut out = Vec::new();
+        reader.fetch(b"chr1", 2..11, &mut out).unwrap();
+        assert_eq!(out, b"GTNNNNacg");
+        out.clear();
+        reader.fetch(b"chr1", 17..26, &mut out).unwrap();
+        assert_eq!(out, b"nnAGGCATT");
+        out.clear();
+        reader.fetch(b"chr3", 1..3, &mut out).unwrap();
+        assert_eq!(out, b"tt");
+
+        assert!(reader.fetch(b"chr1", 20..27, &mut out).is_err());
+        assert!(reader.fetch(b"chrX", 0..1, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_writer_round_trip() {
+        let mut writer = TwoBitWriter::new(Cursor::new(Vec::new()));
+        writer
+            .write_all(&mut FastaReader::with_capacity(5, FASTA))
+            .unwrap();
+        let file = writer.finish().unwrap().into_inner();
+        assert_eq!(file, two_bit(false, 0));
+
+        let mut expected = records(FastaReader::new(FASTA));
+        expected[0].id.truncate(4);
+        assert_eq!(
+            records(TwoBitReader::new(Cursor::new(file)).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_writer_shifts_large_records() {
+        // Records larger than one shift chunk, after existing output.
+        let mut fasta = b">big\n".to_vec();
+        fasta.extend(b"ACGTNacgt".iter().cycle().take(5 * SHIFT_CHUNK_SIZE));
+        fasta.extend(b"\n>small\nGATTACA\n");
+
+        let mut out = Cursor::new(b"prefix".to_vec());
+        out.set_position(6);
+        let mut writer = TwoBitWriter::new(out);
+        writer.write_all(&mut FastaReader::new(&fasta[..])).unwrap();
+        let file = writer.finish().unwrap().into_inner();
+        assert_eq!(&file[..6], b"prefix");
+
+        let mut reader = TwoBitReader::new(Cursor::new(file[6..].to_vec())).unwrap();
+        assert_eq!(reader.sequence_len(b"big").unwrap(), 5 * SHIFT_CHUNK_SIZE);
+        let mut out = Vec::new();
+        reader
+            .fetch(b"big", 4_000_000..4_000_009, &mut out)
+            .unwrap();
+        assert_eq!(out, b"NacgtACGT");
+        out.clear();
+        reader.fetch(b"small", 0..7, &mut out).unwrap();
+        assert_eq!(out, b"GATTACA");
+    }
+
+    #[test]
+    fn test_writer_rejects_duplicate_names() {
+        let mut writer = TwoBitWriter::new(Cursor::new(Vec::new()));
+        writer
+            .write_all(&mut FastaReader::new(&b">a x\nAC\n>a y\nGT\n"[..]))
+            .unwrap();
+        let result = writer.finish();
+        assert!(matches!(result, Err(ReaderError::InvalidFormat { .. })));
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        assert!(TwoBitReader::new(Cursor::new(b">chr1\nACGT\n".to_vec())).is_err());
+
+        let mut file = two_bit(false, 0);
+        file[4] = 2;
+        assert!(TwoBitReader::new(Cursor::new(file)).is_err());
+
+        let mut file = two_bit(false, 0);
+        file[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(TwoBitReader::new(Cursor::new(file)).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_counts_are_rejected() {
+        let file = two_bit(false, 0);
+        // chr1 record: length, then the N block count.
+        let offset = u32::from_le_bytes(file[21..25].try_into().unwrap()) as usize;
+
+        let mut corrupt = file.clone();
+        corrupt[offset + 4..offset + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut reader = TwoBitReader::new(Cursor::new(corrupt)).unwrap();
+        assert!(matches!(
+            reader.next_event(),
+            Some(Err(ReaderError::InvalidFormat { .. }))
+        ));
+
+        let mut corrupt = file;
+        corrupt[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut reader = TwoBitReader::new(Cursor::new(corrupt)).unwrap();
+        let mut out = Vec::new();
+        assert!(matches!(
+            reader.fetch(b"chr1", 0..4, &mut out),
+            Err(ReaderError::InvalidFormat { .. })
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::error::ReaderError;
+use crate::record::Record;
+
+/// Role of one position in a [`BarcodePattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarcodeBase {
+    /// `C`: cell barcode base.
+    Cell,
+    /// `N`: UMI base.
+    Umi,
+    /// `X`: base kept in the read.
+    Keep,
+}
+
+/// Fixed-position barcode layout at the 5' end of a read, in umi_tools
+/// notation (e.g. `CCCCCCCCCCCCCCCCNNNNNNNNNNNN`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BarcodePattern {
+    bases: Vec<BarcodeBase>,
+}
+
+impl BarcodePattern {
+    /// Parses a pattern of `C`, `N` and `X` characters.
+    pub fn parse(pattern: &str) -> Result<Self, ReaderError> {
+        let bases = pattern
+            .bytes()
+            .map(|b| match b {
+                b'C' => Ok(BarcodeBase::Cell),
+                b'N' => Ok(BarcodeBase::Umi),
+                b'X' => Ok(BarcodeBase::Keep),
+                _ => Err(ReaderError::InvalidFormat {
+                    message: format!("Invalid barcode pattern character '{}'", b as char),
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !bases.iter().any(|&b| b != BarcodeBase::Keep) {
+            return Err(ReaderError::InvalidFormat {
+                message: format!("Barcode pattern '{pattern}' has no C or N positions"),
+            });
+        }
+        Ok(Self { bases })
+    }
+
+    /// Number of read bases covered by the pattern.
+    pub fn len(&self) -> usize {
+        self.bases.len()
+    }
+
+    /// Returns `true` if the pattern is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bases.is_empty()
+    }
+}
+
+/// Moves barcode bases from the read sequence into the read name,
+/// umi_tools `extract` style: `name_CELL_UMI` (or `name_UMI`).
+#[derive(Debug, Clone)]
+pub struct UmiExtractor {
+    pattern: BarcodePattern,
+    cell: Vec<u8>,
+    umi: Vec<u8>,
+    tag: Vec<u8>,
+    seq: Vec<u8>,
+    qual: Vec<u8>,
+}
+
+impl UmiExtractor {
+    /// Creates an extractor for `pattern`.
+    pub fn new(pattern: BarcodePattern) -> Self {
+        Self {
+            pattern,
+            cell: Vec::new(),
+            umi: Vec::new(),
+            tag: Vec::new(),
+            seq: Vec::new(),
+            qual: Vec::new(),
+        }
+    }
+
+    /// Extracts the barcode from `record`. Returns `false` and leaves the
+    /// record unchanged if it is shorter than the pattern.
+    pub fn extract(&mut self, record: &mut Record) -> bool {
+        let len = self.pattern.len();
+        if record.seq.len() < len {
+            return false;
+        }
+        let has_qual = record.qual.len() == record.seq.len();
+
+        self.cell.clear();
+        self.umi.clear();
+        self.seq.clear();
+        self.qual.clear();
+
+        for (i, &base) in self.pattern.bases.iter().enumerate() {
+            match base {
+                BarcodeBase::Cell => self.cell.push(record.seq[i]),
+                BarcodeBase::Umi => self.umi.push(record.seq[i]),
+                BarcodeBase::Keep => {
+                    self.seq.push(record.seq[i]);
+                    if has_qual {
+                        self.qual.push(record.qual[i]);
+                    }
+                }
+            }
+        }
+
+        self.tag.clear();
+        for part in [&self.cell, &self.umi] {
+            if !part.is_empty() {
+                self.tag.push(b'_');
+                self.tag.extend_from_slice(part);
+            }
+        }
+
+        record.seq.splice(..len, self.seq.iter().copied());
+        if has_qual {
+            record.qual.splice(..len, self.qual.iter().copied());
+        }
+        self.append_tag(record);
+        true
+    }
+
+    /// Extracts the barcode from `r1` and appends the same tag to the name of
+    /// `r2`. Returns `false` and leaves both unchanged if `r1` is too short.
+    pub fn extract_pair(&mut self, r1: &mut Record, r2: &mut Record) -> bool {
+        if !self.extract(r1) {
+            return false;
+        }
+        self.append_tag(r2);
+        true
+    }
+
+    fn append_tag(&self, record: &mut Record) {
+        let name_end = record.name().len();
+        record
+            .id
+            .splice(name_end..name_end, self.tag.iter().copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_parse() {
+        assert_eq!(BarcodePattern::parse("CCNNX").unwrap().len(), 5);
+        assert!(BarcodePattern::parse("CCZ").is_err());
+        assert!(BarcodePattern::parse("XX").is_err());
+    }
+
+    #[test]
+    fn test_extract_single() {
+        let mut extractor = UmiExtractor::new(BarcodePattern::parse("CCCNNX").unwrap());
+        let mut r = Record::from_parts(b"read1 1:N:0:1", b"ACGTTAGGGG", b"abcdefghij");
+
+        assert!(extractor.extract(&mut r));
+        assert_eq!(&r.id, b"read1_ACG_TT 1:N:0:1");
+        assert_eq!(&r.seq, b"AGGGG");
+        assert_eq!(&r.qual, b"fghij");
+
+        let mut short = Record::from_parts(b"read2", b"ACG", b"abc");
+        assert!(!extractor.extract(&mut short));
+        assert_eq!(&short.seq, b"ACG");
+    }
+
+    #[test]
+    fn test_extract_pair() {
+        let mut extractor = UmiExtractor::new(BarcodePattern::parse("NNNN").unwrap());
+        let mut r1 = Record::from_parts(b"read1/1", b"TTAACCGG", b"IIIIIIII");
+        let mut r2 = Record::from_parts(b"read1/2", b"GGGG", b"IIII");
+
+        assert!(extractor.extract_pair(&mut r1, &mut r2));
+        assert_eq!(&r1.id, b"read1/1_TTAA");
+        assert_eq!(&r2.id, b"read1/2_TTAA");
+        assert_eq!(&r1.seq, b"CCGG");
+        assert_eq!(&r2.seq, b"GGGG");
+    }
+}
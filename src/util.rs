@@ -0,0 +1,15 @@
+use memchr::memchr2;
+
+/// Splits a header into the name, up to the first space or tab, and the
+/// description after it, if any.
+pub(crate) fn split_name(id: &[u8]) -> (&[u8], Option<&[u8]>) {
+    match memchr2(b' ', b'\t', id) {
+        Some(pos) => (&id[..pos], Some(&id[pos + 1..])),
+        None => (id, None),
+    }
+}
+
+/// Parses an ASCII number field, returning `None` on invalid input.
+pub(crate) fn parse_num<T: std::str::FromStr>(field: &[u8]) -> Option<T> {
+    std::str::from_utf8(field).ok()?.parse().ok()
+}
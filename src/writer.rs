@@ -0,0 +1,176 @@
+use std::io::{self, BufWriter, Write};
+
+use crate::record::Record;
+use crate::sam_tag::SamTag;
+
+const DEFAULT_BUFFER_SIZE: usize = 128 * 1024;
+
+/// Buffered FASTA writer.
+pub struct FastaWriter<W: Write> {
+    writer: BufWriter<W>,
+    line_width: Option<usize>,
+}
+
+impl<W: Write> FastaWriter<W> {
+    /// Creates a writer that puts each sequence on a single line.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(DEFAULT_BUFFER_SIZE, writer),
+            line_width: None,
+        }
+    }
+
+    /// Wraps sequence lines at `width` bases.
+    pub fn with_line_width(mut self, width: usize) -> Self {
+        self.line_width = Some(width.max(1));
+        self
+    }
+
+    /// Writes a record. Quality data is ignored.
+    pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        self.write_parts(&record.id, &record.seq)
+    }
+
+    /// Writes a record from its header and sequence.
+    pub fn write_parts(&mut self, id: &[u8], seq: &[u8]) -> io::Result<()> {
+        self.writer.write_all(b">")?;
+        self.writer.write_all(id)?;
+        self.writer.write_all(b"\n")?;
+
+        // An empty sequence is written as one empty line either way.
+        match self.line_width {
+            Some(width) if !seq.is_empty() => {
+                for line in seq.chunks(width) {
+                    self.writer.write_all(line)?;
+                    self.writer.write_all(b"\n")?;
+                }
+            }
+            _ => {
+                self.writer.write_all(seq)?;
+                self.writer.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes buffered output.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.writer.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+/// Buffered FASTQ writer.
+pub struct FastqWriter<W: Write> {
+    writer: BufWriter<W>,
+}
+
+impl<W: Write> FastqWriter<W> {
+    /// Creates a writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(DEFAULT_BUFFER_SIZE, writer),
+        }
+    }
+
+    /// Writes a record.
+    pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        self.write_parts(&record.id, &record.seq, &record.qual)
+    }
+
+    /// Writes a record with `tags` appended to its header, tab-separated.
+    pub fn write_record_with_tags(&mut self, record: &Record, tags: &[SamTag]) -> io::Result<()> {
+        self.writer.write_all(b"@")?;
+        self.writer.write_all(&record.id)?;
+        for tag in tags {
+            self.writer.write_all(b"\t")?;
+            tag.write_to(&mut self.writer)?;
+        }
+        self.write_body(&record.seq, &record.qual)
+    }
+
+    /// Writes a record from its header, sequence and quality string.
+    pub fn write_parts(&mut self, id: &[u8], seq: &[u8], qual: &[u8]) -> io::Result<()> {
+        self.writer.write_all(b"@")?;
+        self.writer.write_all(id)?;
+        self.write_body(seq, qual)
+    }
+
+    fn write_body(&mut self, seq: &[u8], qual: &[u8]) -> io::Result<()> {
+        self.writer.write_all(b"\n")?;
+        self.writer.write_all(seq)?;
+        self.writer.write_all(b"\n+\n")?;
+        self.writer.write_all(qual)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Flushes buffered output.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.writer.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fasta_line_width() {
+        let mut writer = FastaWriter::new(Vec::new()).with_line_width(4);
+        writer
+            .write_record(&Record::from_parts(b"seq1 desc", b"ACGTACGTAC", b""))
+            .unwrap();
+        let out = writer.into_inner().unwrap();
+        assert_eq!(&out, b">seq1 desc\nACGT\nACGT\nAC\n");
+    }
+
+    #[test]
+    fn test_fasta_empty_record() {
+        for width in [None, Some(4)] {
+            let mut writer = FastaWriter::new(Vec::new());
+            if let Some(width) = width {
+                writer = writer.with_line_width(width);
+            }
+            writer.write_parts(b"empty", b"").unwrap();
+            writer.write_parts(b"next", b"AC").unwrap();
+            let out = writer.into_inner().unwrap();
+            assert_eq!(&out, b">empty\n\n>next\nAC\n");
+        }
+    }
+
+    #[test]
+    fn test_fastq_writer() {
+        let mut writer = FastqWriter::new(Vec::new());
+        writer
+            .write_record(&Record::from_parts(b"read1", b"ACGT", b"IIII"))
+            .unwrap();
+        writer
+            .write_record(&Record::from_parts(b"read2", b"TG", b"HH"))
+            .unwrap();
+        let out = writer.into_inner().unwrap();
+        assert_eq!(&out, b"@read1\nACGT\n+\nIIII\n@read2\nTG\n+\nHH\n");
+    }
+
+    #[test]
+    fn test_fastq_writer_tags() {
+        let mut writer = FastqWriter::new(Vec::new());
+        let tags = [
+            SamTag::string(*b"CB", b"ACGT"),
+            SamTag::string(*b"UB", b"TTGG"),
+        ];
+        writer
+            .write_record_with_tags(&Record::from_parts(b"read1", b"AC", b"II"), &tags)
+            .unwrap();
+        let out = writer.into_inner().unwrap();
+        assert_eq!(&out, b"@read1\tCB:Z:ACGT\tUB:Z:TTGG\nAC\n+\nII\n");
+    }
+}
@@ -2,7 +2,7 @@ use std::fs::File;
 use std::path::Path;
 
 use flate2::read::GzDecoder;
-use seq_events::{Event, FastaReader, FastqReader};
+use seq_events::{Event, FastaReader, FastqReader, Stats};
 
 const TEST_DATA_DIR: &str = "tests/test_data";
 
@@ -239,3 +239,43 @@ fn test_fastq_lf_vs_crlf_consistency() {
 
     assert_eq!(lf_stats, crlf_stats);
 }
+
+#[test]
+fn test_stats_matches_helpers_fasta() {
+    let path = fasta_dir().join("influenza.fasta");
+    if !path.exists() {
+        panic!("File not found: {}", path.display());
+    }
+
+    let mut stats = Stats::new();
+    stats
+        .observe_all(&mut FastaReader::new(File::open(&path).unwrap()))
+        .unwrap();
+    let summary = stats.summary();
+    let (record_count, total_seq_len, _) =
+        count_fasta_stats(FastaReader::new(File::open(&path).unwrap()));
+
+    assert_eq!(summary.records, record_count as u64);
+    assert_eq!(summary.total_length, total_seq_len as u64);
+    assert!(summary.n50 >= summary.n90);
+    assert!(summary.gc_content > 0.3 && summary.gc_content < 0.6);
+}
+
+#[test]
+fn test_stats_matches_helpers_fastq() {
+    let path = fastq_dir().join("sample.fastq");
+    if !path.exists() {
+        panic!("File not found: {}", path.display());
+    }
+
+    let mut stats = Stats::new();
+    stats
+        .observe_all(&mut FastqReader::new(File::open(&path).unwrap()))
+        .unwrap();
+    let summary = stats.summary();
+
+    assert_eq!(summary.records, 5);
+    assert_eq!(summary.total_length, 252);
+    assert_eq!(summary.q30_fraction, Some(1.0));
+    assert_eq!(summary.position_quality.len() as u64, summary.max_length);
+}